@@ -0,0 +1,245 @@
+use super::spline::SplineRef;
+use super::{Evaluator, Result, SplineCurve, SplineError};
+
+/// Five point Gauss-Legendre nodes and weights, on the interval `-1..=1`.
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0.0, 0.568_888_888_888_888_9),
+    (-0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (0.538_469_310_105_683_1, 0.478_628_670_499_366_5),
+    (-0.906_179_845_938_664, 0.236_926_885_056_189_1),
+    (0.906_179_845_938_664, 0.236_926_885_056_189_1),
+];
+
+/// Relative tolerance for the arc length integrals, and for the parameter values found for a given arc length.
+const TOLERANCE: f64 = 1E-12;
+
+/// Largest number of interval bisections in the adaptive quadrature.
+const MAX_DEPTH: usize = 30;
+
+/**
+ * Cursor for evaluating a spline curve at constant speed, using arc length as parameter
+ *
+ * The evaluator holds a table with the arc lengths at the knots of the curve, and an [Evaluator] for the points on
+ * the curve; for each arc length, the parameter value of the curve is found by Newton's method, within the knot
+ * interval containing it. Arc lengths outside `0..=length()` are clamped to the ends of the curve.
+ */
+#[derive(Debug, Clone)]
+pub struct ArcLengthEvaluator<'a, const K: usize, const N: usize> {
+    e: Evaluator<'a, K, N>,
+    table: ArcLengthTable,
+}
+
+impl<'a, const K: usize, const N: usize> ArcLengthEvaluator<'a, K, N> {
+    pub fn new(s: &'a SplineCurve<K, N>) -> Result<Self> {
+        let table = ArcLengthTable::new(&s.spline_ref())?;
+        Ok(Self { e: s.evaluator()?, table })
+    }
+
+    /// Total arc length of the curve, over its domain.
+    pub fn length(&self) -> f64 {
+        self.table.length()
+    }
+
+    /// Parameter value of the curve at arc length `s`, measured from the start of its domain
+    /// 
+    /// A [NonFiniteInput][SplineError::NonFiniteInput] error is returned if `s` is NaN or infinite.
+    pub fn parameter(&self, s: f64) -> Result<f64> {
+        self.table.parameter(&self.e.spline_curve().spline_ref(), s)
+    }
+
+    /// Point on the curve at arc length `s`, measured from the start of its domain
+    ///
+    /// Points at equally spaced arc lengths are equally spaced along the curve, for example to move along a curve
+    /// at constant speed.
+    pub fn eval(&mut self, s: f64) -> Result<[f64; N]> {
+        let u = self.parameter(s)?;
+        self.e.eval(u)
+    }
+}
+
+/// Cumulative arc lengths of a spline curve at the knots within its domain.
+#[derive(Debug, Clone)]
+pub(crate) struct ArcLengthTable {
+    knots: Vec<f64>,
+    lengths: Vec<f64>,
+}
+
+impl ArcLengthTable {
+    pub fn new(s: &SplineRef<'_>) -> Result<Self> {
+        s.check_coefficients()?;
+        let (tb, te) = s.domain();
+        let mut knots = vec![tb];
+        knots.extend(s.t.iter().filter(|&&x| x > tb && x < te));
+        knots.push(te);
+        knots.dedup();
+        let mut lengths = Vec::with_capacity(knots.len());
+        let mut sum = 0.0;
+        lengths.push(sum);
+        for w in knots.windows(2) {
+            sum += interval_length(s, w[0], w[1]);
+            lengths.push(sum);
+        }
+        Ok(Self { knots, lengths })
+    }
+
+    pub fn length(&self) -> f64 {
+        self.lengths[self.lengths.len() - 1]
+    }
+
+    /// Parameter value at arc length `length`, using Newton's method, safeguarded by bisection.
+    pub fn parameter(&self, s: &SplineRef<'_>, length: f64) -> Result<f64> {
+        if !length.is_finite() {
+            return Err(SplineError::NonFiniteInput { value: length });
+        }
+        let total = self.length();
+        if length <= 0.0 {
+            return Ok(self.knots[0]);
+        } else if length >= total {
+            return Ok(self.knots[self.knots.len() - 1]);
+        }
+        let j = self.lengths.partition_point(|&l| l <= length).saturating_sub(1).min(self.knots.len() - 2);
+        let (mut lo, mut hi) = (self.knots[j], self.knots[j + 1]);
+        let target = length - self.lengths[j];
+        let mut u = lo + (hi - lo) * target / (self.lengths[j + 1] - self.lengths[j]);
+        let mut work = Work::new(s);
+        for _ in 0..100 {
+            let g = interval_length(s, self.knots[j], u) - target;
+            if g.abs() <= TOLERANCE * total {
+                break;
+            }
+            if g > 0.0 {
+                hi = u;
+            } else {
+                lo = u;
+            }
+            let speed = work.speed(s, u);
+            let newton = u - g / speed;
+            u = if speed > 0.0 && newton > lo && newton < hi { newton } else { 0.5 * (lo + hi) };
+        }
+        Ok(u)
+    }
+}
+
+/// Arc length of a spline curve for parameter values from `a` to `b`, see [SplineCurve::arc_length]
+pub(crate) fn arc_length(s: &SplineRef<'_>, a: f64, b: f64) -> Result<f64> {
+    s.check_coefficients()?;
+    if let Some(&value) = [a, b].iter().find(|x| !x.is_finite()) {
+        return Err(SplineError::NonFiniteInput { value });
+    }
+    let (tb, te) = s.domain();
+    let (sign, a, b) = if a <= b { (1.0, a.clamp(tb, te), b.clamp(tb, te)) } else { (-1.0, b.clamp(tb, te), a.clamp(tb, te)) };
+    let mut knots = vec![a];
+    knots.extend(s.t.iter().filter(|&&x| x > a && x < b));
+    knots.push(b);
+    Ok(sign * knots.windows(2).map(|w| interval_length(s, w[0], w[1])).sum::<f64>())
+}
+
+/// Arc length for parameter values from `a` to `b`, with `a<=b`, and without knots in between, using adaptive
+/// Gauss-Legendre quadrature of the speed of the curve.
+fn interval_length(s: &SplineRef<'_>, a: f64, b: f64) -> f64 {
+    if b <= a {
+        return 0.0;
+    }
+    let mut work = Work::new(s);
+    let mut speed = |u: f64| work.speed(s, u);
+    let whole = gauss_legendre(&mut speed, a, b);
+    adaptive(&mut speed, a, b, whole, TOLERANCE * whole.max(f64::MIN_POSITIVE), MAX_DEPTH)
+}
+
+fn gauss_legendre(f: &mut impl FnMut(f64) -> f64, a: f64, b: f64) -> f64 {
+    let (m, h) = (0.5 * (a + b), 0.5 * (b - a));
+    h * GAUSS_LEGENDRE.iter().map(|(x, w)| w * f(m + h * x)).sum::<f64>()
+}
+
+/// Adaptive quadrature, bisecting intervals until the error estimate is within the tolerance, which also ends for
+/// NaN values, or when the interval or the tolerance can not be halved anymore.
+fn adaptive(f: &mut impl FnMut(f64) -> f64, a: f64, b: f64, whole: f64, tolerance: f64, depth: usize) -> f64 {
+    let m = 0.5 * (a + b);
+    let (left, right) = (gauss_legendre(f, a, m), gauss_legendre(f, m, b));
+    let error = (left + right - whole).abs();
+    let converged = error <= tolerance || error.is_nan();
+    let halving = a < m && m < b && 0.5 * tolerance < tolerance;
+    if depth == 0 || converged || !halving {
+        left + right
+    } else {
+        adaptive(f, a, m, left, 0.5 * tolerance, depth - 1) + adaptive(f, m, b, right, 0.5 * tolerance, depth - 1)
+    }
+}
+
+/// Work arrays for evaluating the first derivative of a curve at single parameter values.
+struct Work {
+    i: usize,
+    d: Vec<f64>,
+    v: Vec<f64>,
+}
+
+impl Work {
+    fn new(s: &SplineRef<'_>) -> Self {
+        Self { i: s.k, d: vec![0.0; s.k + 1], v: vec![0.0; s.n] }
+    }
+
+    /// Length of the first derivative, within the domain of the curve.
+    fn speed(&mut self, s: &SplineRef<'_>, u: f64) -> f64 {
+        // never fails, as the parameter values are within the domain
+        let _ = s.evaluate_point(u, 1, 1, &mut self.i, &mut self.d, &mut self.v);
+        self.v.iter().map(|x| x * x).sum::<f64>().sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SplineCurve, SplineError};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn arc_length() {
+        // straight line, with a non-uniform speed
+        let s: SplineCurve<2, 2> = SplineCurve::new(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0], vec![0.0, 0.3, 3.0, 0.0, 0.4, 4.0]);
+        assert_abs_diff_eq!(s.arc_length(0.0, 1.0).unwrap(), 5.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.arc_length(1.0, 0.0).unwrap(), -5.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.arc_length(-1.0, 0.5).unwrap(), s.evaluate(&[0.5]).unwrap()[1] / 0.8, epsilon = 1E-12);
+        let u = s.parameter_at_arc_length(2.5).unwrap();
+        assert_abs_diff_eq!(s.evaluate(&[u]).unwrap()[..], [1.5, 2.0][..], epsilon = 1E-10);
+
+        // quarter circle, approximated by a cubic curve, with a radius close to one
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+            vec![1.0, 1.0, 0.552_284_749_831, 0.0, 0.0, 0.552_284_749_831, 1.0, 1.0],
+        );
+        assert_abs_diff_eq!(s.arc_length(0.0, 1.0).unwrap(), std::f64::consts::FRAC_PI_2, epsilon = 1E-3);
+
+        // moving at constant speed along a curve with knots, and a corner at the double knot at 1
+        let s: SplineCurve<2, 2> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 1.0, 1.0, 2.0, 3.0, 3.0, 3.0],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 0.0, 1.0, 0.0, 2.0, 0.0, 1.0],
+        );
+        let mut e = s.arc_length_evaluator().unwrap();
+        let length = e.length();
+        assert_abs_diff_eq!(length, s.arc_length(0.0, 3.0).unwrap(), epsilon = 1E-12);
+        assert_abs_diff_eq!(s.arc_length(0.0, 1.7).unwrap() + s.arc_length(1.7, 3.0).unwrap(), length, epsilon = 1E-12);
+        for j in 0..=20 {
+            let l = length * j as f64 / 20.0;
+            let u = e.parameter(l).unwrap();
+            assert_abs_diff_eq!(s.arc_length(0.0, u).unwrap(), l, epsilon = 1E-9);
+        }
+        assert_abs_diff_eq!(e.eval(length).unwrap()[..], [5.0, 1.0][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(e.eval(-1.0).unwrap()[..], [0.0, 0.0][..], epsilon = 1E-12);
+
+        // polyline, with exact segment lengths, and an unclamped straight line, with domain 1..=2
+        let s: SplineCurve<1, 2> = SplineCurve::new(vec![0.0, 0.0, 1.0, 3.0, 3.0], vec![0.0, 3.0, 3.0, 0.0, 4.0, 6.0]);
+        assert_abs_diff_eq!(s.arc_length(0.0, 3.0).unwrap(), 7.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.parameter_at_arc_length(6.0).unwrap(), 2.0, epsilon = 1E-10);
+        let l: SplineCurve<1, 1> = SplineCurve::new(vec![0.0, 1.0, 2.0, 3.0], vec![0.0, 2.0]);
+        assert_abs_diff_eq!(l.arc_length(0.0, 3.0).unwrap(), 2.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(l.parameter_at_arc_length(0.5).unwrap(), 1.25, epsilon = 1E-10);
+
+        // non-finite bounds are rejected, and NaN coefficients do not prevent the quadrature from ending
+        assert!(matches!(s.arc_length(f64::NAN, 1.0), Err(SplineError::NonFiniteInput { .. })));
+        assert!(matches!(s.arc_length(0.0, f64::INFINITY), Err(SplineError::NonFiniteInput { .. })));
+        assert!(matches!(s.parameter_at_arc_length(f64::NAN), Err(SplineError::NonFiniteInput { .. })));
+        assert!(matches!(e.parameter(f64::NAN), Err(SplineError::NonFiniteInput { .. })));
+        assert!(matches!(e.eval(f64::NEG_INFINITY), Err(SplineError::NonFiniteInput { .. })));
+        let s: SplineCurve<1, 2> = SplineCurve::new(vec![0.0, 0.0, 1.0, 1.0], vec![0.0, f64::NAN, 0.0, 1.0]);
+        assert!(s.arc_length(0.0, 1.0).unwrap().is_nan());
+    }
+}
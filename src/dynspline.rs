@@ -0,0 +1,347 @@
+use super::{Result, SplineError};
+#[cfg(feature="plot")]
+use super::plot::plot_base;
+use super::spline::{periodic_extension, SplineRef};
+use super::{Extrapolate, JoinOptions, Side, SplineCurve};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/**
+ * B-Spline Curve Knot/Coefficient Representation, with its degree and dimension only known at runtime
+ * 
+ * Use this, for example, to read spline curves from JSON files with arbitrary degree and dimension.
+ * It can be converted into a [SplineCurve] with matching degree and dimension using `try_from` or `try_into`,
+ * and a [SplineCurve] can be converted into a `DynSplineCurve` using `from` or `into`.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SplineCurveData")]
+pub struct DynSplineCurve {
+    pub t: Vec<f64>, // Knot values
+    pub c: Vec<f64>, // b-Spline coefficients
+    pub k: usize,        // Spline degree
+    pub n: usize,        // Spline dimension
+    #[serde(default, skip_serializing_if = "Extrapolate::is_clamp")]
+    pub extrapolate: Extrapolate, // Evaluation outside the domain t[k]..=t[n-k-1]
+}
+
+/// Unchecked JSON representation of a spline curve, validated when converted into a [DynSplineCurve].
+#[derive(Deserialize)]
+struct SplineCurveData {
+    t: Vec<f64>,
+    c: Vec<f64>,
+    k: usize,
+    n: usize,
+    #[serde(default)]
+    extrapolate: Extrapolate,
+}
+
+impl TryFrom<SplineCurveData> for DynSplineCurve {
+    type Error = SplineError;
+
+    fn try_from(s: SplineCurveData) -> Result<Self> {
+        let sc = Self::new(s.t, s.c, s.k, s.n).with_extrapolation(s.extrapolate);
+        sc.validate()?;
+        Ok(sc)
+    }
+}
+
+impl<const K: usize, const N: usize> From<SplineCurve<K, N>> for DynSplineCurve {
+    fn from(s: SplineCurve<K, N>) -> Self {
+        Self::new(s.t, s.c, K, N).with_extrapolation(s.extrapolate)
+    }
+}
+
+impl<const K: usize, const N: usize> TryFrom<DynSplineCurve> for SplineCurve<K, N> {
+    type Error = SplineError;
+
+    fn try_from(s: DynSplineCurve) -> Result<Self> {
+        if s.k != K {
+            return Err(SplineError::DegreeMismatch { expected: K, got: s.k });
+        }
+        if s.n != N {
+            return Err(SplineError::DimensionMismatch { expected: N, got: s.n });
+        }
+        Ok(Self::new(s.t, s.c).with_extrapolation(s.extrapolate))
+    }
+}
+
+impl DynSplineCurve {
+    pub fn new(t: Vec<f64>, c: Vec<f64>, k: usize, n: usize) -> Self {
+        Self { t, c, k, n, extrapolate: Extrapolate::Clamp }
+    }
+
+    pub fn try_new(t: Vec<f64>, c: Vec<f64>, k: usize, n: usize) -> Result<Self> {
+        Self::try_from(SplineCurveData { t, c, k, n, extrapolate: Extrapolate::Clamp })
+    }
+
+    /// Creates a closed, periodic, spline curve from the knots and control points of a single period, see
+    /// [SplineCurve::periodic].
+    pub fn periodic(t: Vec<f64>, c: Vec<f64>, k: usize, n: usize) -> Result<Self> {
+        let (t, c) = periodic_extension(&t, &c, k, n)?;
+        Self::try_new(t, c, k, n).map(|s| s.with_extrapolation(Extrapolate::Periodic))
+    }
+
+    /// Checks all the invariants of the spline curve, see [SplineCurve::validate].
+    pub fn validate(&self) -> Result<()> {
+        let errors = self.spline_ref().validate();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SplineError::Invalid(errors))
+        }
+    }
+
+    /// Sets the extrapolation method, used for all evaluations outside the domain of the curve, see [Extrapolate].
+    pub fn with_extrapolation(mut self, extrapolate: Extrapolate) -> Self {
+        self.extrapolate = extrapolate;
+        self
+    }
+
+    pub(crate) fn spline_ref(&self) -> SplineRef<'_> {
+        SplineRef { t: &self.t, c: &self.c, k: self.k, n: self.n, extrapolate: self.extrapolate, side: Side::Right }
+    }
+
+    /// Reads a spline curve from its JSON representation, with any degree `k` and dimension `n`
+    /// 
+    /// Only malformed JSON data gives a [SplineError::Json] error; the curve is validated after reading, and an
+    /// invalid curve gives a [SplineError::Invalid] error, listing all its problems.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let data: SplineCurveData = serde_json::from_str(json)?;
+        Self::try_from(data)
+    }
+
+    /// JSON representation of the spline curve, in the same format as used by [SplineCurve::to_json].
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    #[cfg(feature="plot")]
+    pub fn plot(self, filepath: &str, wxh: (u32, u32)) -> Result<()> {
+        plot_base(&self, filepath, wxh, None, None, false)
+    }
+
+    #[cfg(feature="plot")]
+    pub fn plot_with_parameter(self, filepath: &str, wxh: (u32, u32), u:Option<&[f64]>) -> Result<()> {
+        plot_base(&self, filepath, wxh, u, None, false)
+    }
+
+    #[cfg(feature="plot")]
+    pub fn plot_with_control_points(self, filepath: &str, wxh: (u32, u32)) -> Result<()> {
+        plot_base(&self, filepath, wxh, None, None, true)
+    }
+
+    #[cfg(feature="plot")]
+    pub fn plot_with_data(self, filepath: &str, wxh: (u32, u32), xy: &[f64]) -> Result<()> {
+        plot_base(&self, filepath, wxh, None, Some(xy), false)
+    }
+
+    #[cfg(feature="plot")]
+    pub fn plot_with_control_points_and_data(self, filepath: &str, wxh: (u32, u32), xy: &[f64]) -> Result<()> {
+        plot_base(&self, filepath, wxh, None, Some(xy), true)
+    }
+
+    /// Calulates spline coordinates for a collection of parameter values, see [SplineCurve::evaluate].
+    pub fn evaluate(&self, u: &[f64]) -> Result<Vec<f64>> {
+        self.spline_ref().evaluate(u, 0, 0)
+    }
+
+    /// Calculates the derivatives of a given order for a collection of parameter values, see [SplineCurve::evaluate_derivative].
+    pub fn evaluate_derivative(&self, u: &[f64], order: usize) -> Result<Vec<f64>> {
+        self.spline_ref().evaluate(u, order, order)
+    }
+
+    /// Calculates one-sided limits of the derivatives of a given order, see [SplineCurve::evaluate_limit].
+    pub fn evaluate_limit(&self, u: &[f64], order: usize, side: Side) -> Result<Vec<f64>> {
+        self.spline_ref().with_side(side).evaluate(u, order, order)
+    }
+
+    /// Calculates the spline coordinates, and their first `m` derivatives, see [SplineCurve::evaluate_with_derivatives].
+    pub fn evaluate_with_derivatives(&self, u: &[f64], m: usize) -> Result<Vec<f64>> {
+        self.spline_ref().evaluate(u, 0, m)
+    }
+
+    /// Domain of the curve, see [SplineCurve::domain].
+    pub fn domain(&self) -> (f64, f64) {
+        self.spline_ref().domain()
+    }
+
+    /// Checks if the knot vector is clamped, see [SplineCurve::is_clamped].
+    pub fn is_clamped(&self) -> bool {
+        self.spline_ref().is_clamped()
+    }
+
+    /// Clamped form of a curve with an unclamped knot vector, see [SplineCurve::clamped].
+    pub fn clamped(&self) -> Result<Self> {
+        let (t, c) = self.spline_ref().clamped()?;
+        Ok(Self::new(t, c, self.k, self.n).with_extrapolation(self.extrapolate))
+    }
+
+    /// Inserts the knot `u`, `multiplicity` times, see [SplineCurve::insert_knot].
+    pub fn insert_knot(&self, u: f64, multiplicity: usize) -> Result<Self> {
+        let (t, c) = self.spline_ref().insert_knot(u, multiplicity)?;
+        Ok(Self::new(t, c, self.k, self.n).with_extrapolation(self.extrapolate))
+    }
+
+    /// Inserts a collection of knots at once, see [SplineCurve::refine].
+    pub fn refine(&self, u: &[f64]) -> Result<Self> {
+        let (t, c) = self.spline_ref().refine(u)?;
+        Ok(Self::new(t, c, self.k, self.n).with_extrapolation(self.extrapolate))
+    }
+
+    /// Simplifies the curve by removing interior knots, see [SplineCurve::remove_knots].
+    pub fn remove_knots(&self, tolerance: f64) -> Result<(Self, f64)> {
+        let (t, c, bound) = self.spline_ref().remove_knots(tolerance)?;
+        Ok((Self::new(t, c, self.k, self.n).with_extrapolation(self.extrapolate.sub_curve()), bound))
+    }
+
+    /// Part of the curve for parameter values from `a` to `b`, see [SplineCurve::trim].
+    pub fn trim(&self, a: f64, b: f64) -> Result<Self> {
+        let (t, c) = self.spline_ref().trim(a, b)?;
+        Ok(Self::new(t, c, self.k, self.n).with_extrapolation(self.extrapolate.sub_curve()))
+    }
+
+    /// Splits the curve at the parameter value `u`, see [SplineCurve::split_at].
+    pub fn split_at(&self, u: f64) -> Result<(Self, Self)> {
+        let (tb, te) = self.domain();
+        Ok((self.trim(tb, u)?, self.trim(u, te)?))
+    }
+
+    /// Concatenates spline curves, with equal degree and dimension, end-to-end, see [SplineCurve::concatenate].
+    pub fn concatenate(curves: &[Self], options: JoinOptions) -> Result<Self> {
+        let refs: Vec<SplineRef<'_>> = curves.iter().map(|s| s.spline_ref()).collect();
+        let (t, c) = crate::join::concatenate(&refs, options)?;
+        Ok(Self::new(t, c, curves[0].k, curves[0].n).with_extrapolation(curves[0].extrapolate.sub_curve()))
+    }
+
+    /// The same curve, traversed in the opposite direction, see [SplineCurve::reversed].
+    pub fn reversed(&self) -> Self {
+        let (t, c) = self.spline_ref().reversed();
+        Self::new(t, c, self.k, self.n).with_extrapolation(self.extrapolate)
+    }
+
+    /// The same curve, with its domain mapped linearly onto `a..=b`, see [SplineCurve::reparametrize].
+    pub fn reparametrize(&self, a: f64, b: f64) -> Result<Self> {
+        let t = self.spline_ref().reparametrize(a, b)?;
+        Ok(Self::new(t, self.c.clone(), self.k, self.n).with_extrapolation(self.extrapolate))
+    }
+
+    /// Applies the affine transform `matrix * x + translation` to the curve, see [SplineCurve::transform]
+    /// 
+    /// The `n` by `n` matrix is given row by row, and the translation should have `n` values.
+    pub fn transform(&self, matrix: &[f64], translation: &[f64]) -> Result<Self> {
+        if matrix.len() != self.n * self.n {
+            return Err(SplineError::DimensionMismatch { expected: self.n * self.n, got: matrix.len() });
+        } else if translation.len() != self.n {
+            return Err(SplineError::DimensionMismatch { expected: self.n, got: translation.len() });
+        }
+        let c = self.spline_ref().transform(matrix, translation);
+        Ok(Self::new(self.t.clone(), c, self.k, self.n).with_extrapolation(self.extrapolate))
+    }
+
+    /// Represents the curve exactly as a spline curve of a higher degree `k`, see [SplineCurve::elevate_degree].
+    pub fn elevate_degree(&self, k: usize) -> Result<Self> {
+        if k < self.k {
+            return Err(SplineError::DegreeMismatch { expected: self.k, got: k });
+        }
+        let (t, c) = self.spline_ref().elevate_degree(k)?;
+        Ok(Self::new(t, c, k, self.n).with_extrapolation(self.extrapolate))
+    }
+
+    /// Approximates the curve by a spline curve of a lower degree `k`, see [SplineCurve::reduce_degree].
+    pub fn reduce_degree(&self, k: usize, tolerance: f64) -> Result<(Self, f64)> {
+        if self.k == 0 {
+            return Err(SplineError::ZeroDegree);
+        } else if k >= self.k {
+            return Err(SplineError::DegreeMismatch { expected: self.k - 1, got: k });
+        }
+        let (t, c, error) = self.spline_ref().reduce_degree(k)?;
+        if error > tolerance {
+            return Err(SplineError::ToleranceExceeded { tolerance, error });
+        }
+        Ok((Self::new(t, c, k, self.n).with_extrapolation(self.extrapolate.sub_curve()), error))
+    }
+
+    /// Length of the curve, for parameter values from `a` to `b`, see [SplineCurve::arc_length].
+    pub fn arc_length(&self, a: f64, b: f64) -> Result<f64> {
+        crate::arclength::arc_length(&self.spline_ref(), a, b)
+    }
+
+    /// Parameter value at which the length of the curve is `s`, see [SplineCurve::parameter_at_arc_length].
+    pub fn parameter_at_arc_length(&self, s: f64) -> Result<f64> {
+        let table = crate::arclength::ArcLengthTable::new(&self.spline_ref())?;
+        table.parameter(&self.spline_ref(), s)
+    }
+
+    /// Derivative of the spline curve, as a new spline curve with degree `k-1`.
+    pub fn derivative(&self) -> Result<Self> {
+        let (t, c) = self.spline_ref().derivative()?;
+        Ok(Self::new(t, c, self.k - 1, self.n).with_extrapolation(self.extrapolate))
+    }
+
+    /// Antiderivative of the spline curve, as a new spline curve with degree `k+1`, see [SplineCurve::antiderivative].
+    pub fn antiderivative(&self) -> Result<Self> {
+        let (t, c) = self.spline_ref().antiderivative()?;
+        Ok(Self::new(t, c, self.k + 1, self.n).with_extrapolation(self.extrapolate))
+    }
+
+    /// Definite integrals of the `n` coordinate functions of the curve, see [SplineCurve::integrate].
+    pub fn integrate(&self, a: f64, b: f64) -> Result<Vec<f64>> {
+        self.spline_ref().integrate(a, b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DynSplineCurve;
+    use crate::{CubicSpline2D, SplineCurve, SplineError};
+    use approx::assert_abs_diff_eq;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn dyn_from_json() {
+        let json = r#"{"t":[0,0,0,0,0,0,0,0,1,1,1,1,1,1,1,1],"c":[0,1,0,1,0,1,0,1],"k":7,"n":1}"#;
+        let s = DynSplineCurve::from_json(json).unwrap();
+        assert_eq!((s.k, s.n), (7, 1));
+        let y = s.evaluate(&[0.0, 0.5, 1.0]).unwrap();
+        assert_abs_diff_eq!(y[..], [0.0, 0.5, 1.0][..], epsilon = 1E-12);
+
+        let ds = s.derivative().unwrap();
+        assert_eq!(ds.k, 6);
+        assert_abs_diff_eq!(ds.evaluate(&[0.5]).unwrap()[..], s.evaluate_derivative(&[0.5], 1).unwrap()[..], epsilon = 1E-12);
+
+        let r = DynSplineCurve::from_json(&s.to_json().unwrap()).unwrap();
+        assert_eq!(r.t, s.t);
+        assert_eq!(r.c, s.c);
+
+        assert!(matches!(
+            DynSplineCurve::from_json(r#"{"t":[0,0,1,1],"c":[0,1,2],"k":1,"n":1}"#),
+            Err(SplineError::Invalid(errors)) if matches!(errors[..], [SplineError::CoefficientCountMismatch { expected: 2, got: 3 }])
+        ));
+        assert!(matches!(DynSplineCurve::from_json(r#"{"t":[0,0,1,1],"k":1,"n":1}"#), Err(SplineError::Json(_))));
+        assert!(matches!(
+            DynSplineCurve::try_new(vec![0.0, 0.0, 1.0, 1.0], vec![0.0, 1.0, 2.0], 1, 1),
+            Err(SplineError::Invalid(errors)) if matches!(errors[..], [SplineError::CoefficientCountMismatch { expected: 2, got: 3 }])
+        ));
+        assert!(matches!(DynSplineCurve::new(vec![0.0, 0.0, 1.0, 1.0], vec![], 1, 0).evaluate(&[0.5]), Err(SplineError::ZeroDimension)));
+    }
+
+    #[test]
+    fn dyn_conversions() {
+        let s = CubicSpline2D::new(
+            vec![1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+            vec![0.0, 0.5, 1.0, 3.0, 2.0, -3.0, 3.0, -3.0]
+        );
+        let u = [1.0, 1.2, 1.5, 2.0];
+        let xy = s.evaluate(&u).unwrap();
+
+        let ds = DynSplineCurve::from(s);
+        assert_eq!((ds.k, ds.n), (3, 2));
+        assert_abs_diff_eq!(ds.evaluate(&u).unwrap()[..], xy[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(ds.integrate(1.0, 2.0).unwrap()[..], [1.125, -0.25][..], epsilon = 1E-12);
+
+        assert!(matches!(SplineCurve::<3, 1>::try_from(ds.clone()), Err(SplineError::DimensionMismatch { expected: 1, got: 2 })));
+        assert!(matches!(SplineCurve::<2, 2>::try_from(ds.clone()), Err(SplineError::DegreeMismatch { expected: 2, got: 3 })));
+        let s = CubicSpline2D::try_from(ds).unwrap();
+        assert_abs_diff_eq!(s.evaluate(&u).unwrap()[..], xy[..], epsilon = 1E-12);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fmt;
+
+/// Errors reported by the spline curve functions in this crate.
+#[derive(Debug)]
+pub enum SplineError {
+    /// Less coefficients than required for the degree of the spline curve, for each dimension.
+    TooFewCoefficients { required: usize, got: usize },
+    /// Less knots than required for the degree of the spline curve.
+    TooFewKnots { required: usize, got: usize },
+    /// The number of coefficient values does not match the number of knots, degree and dimension.
+    CoefficientCountMismatch { expected: usize, got: usize },
+    /// Knot values decreasing, with `t[index] > t[index+1]`.
+    NonMonotoneKnots { index: usize },
+    /// Knot value `t[index]` is NaN or infinite.
+    NonFiniteKnot { index: usize },
+    /// Coefficient value `c[index]` is NaN or infinite.
+    NonFiniteCoefficient { index: usize },
+    /// Knot value `t[index]` repeated more than `max` times, with `max` the degree of the curve plus one.
+    KnotMultiplicity { index: usize, multiplicity: usize, max: usize },
+    /// Domain of the spline curve, `t[k]..=t[n-k-1]`, has zero length.
+    EmptyDomain,
+    /// All the invariants violated by a spline curve, as found by validation.
+    Invalid(Vec<SplineError>),
+    /// Parameter values not sorted in increasing order, at `u[index]`.
+    UnsortedParameters { index: usize },
+    /// Parameter value `u` outside the domain of the spline curve, with `distance` the (signed) distance to the nearest end.
+    OutOfDomain { u: f64, distance: f64 },
+    /// Key not found in a [SplineCurves][crate::SplineCurves] table.
+    KeyNotFound(String),
+    /// Spline degree not as expected, for example for a [SplineCurve][crate::SplineCurve] created from a JSON file.
+    DegreeMismatch { expected: usize, got: usize },
+    /// Spline dimension not as expected, for example for a [SplineCurve][crate::SplineCurve] created from a JSON file.
+    DimensionMismatch { expected: usize, got: usize },
+    /// Operation not possible for a spline curve of degree zero, such as taking its derivative.
+    ZeroDegree,
+    /// Spline curve with dimension zero.
+    ZeroDimension,
+    /// Distance between the end point of a curve, and the start point of curve `index`, larger than allowed, when
+    /// concatenating curves.
+    Gap { index: usize, distance: f64 },
+    /// Domain of curve `index` not starting at the end of the domain of the previous curve, when concatenating curves
+    /// without reparametrization.
+    ParameterGap { index: usize, distance: f64 },
+    /// No curves given to concatenate.
+    NoCurves,
+    /// Singular system of linear equations, for example when fitting a spline curve at coinciding parameter values.
+    SingularSystem,
+    /// Approximation error larger than the requested tolerance, as in degree reduction.
+    ToleranceExceeded { tolerance: f64, error: f64 },
+    /// Input value, such as a parameter value or an arc length, is NaN or infinite.
+    NonFiniteInput { value: f64 },
+    /// Rotation axis with zero length, or with values which are NaN or infinite.
+    InvalidAxis,
+    /// Errors generated in the plot routines.
+    Plot(String),
+    /// Errors in reading or writing the JSON representation of a spline curve.
+    Json(serde_json::Error),
+    /// Input and output errors.
+    Io(std::io::Error),
+}
+
+impl fmt::Display for SplineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SplineError::TooFewCoefficients { required, got } => 
+                write!(f, "Need at least {} coefficients for each dimension, got {}", required, got),
+            SplineError::TooFewKnots { required, got } => 
+                write!(f, "Need at least {} knots, got {}", required, got),
+            SplineError::CoefficientCountMismatch { expected, got } => 
+                write!(f, "Expected {} coefficient values, got {}", expected, got),
+            SplineError::NonMonotoneKnots { index } => 
+                write!(f, "Knot values should be non-decreasing, but t[{}] > t[{}]", index, index + 1),
+            SplineError::NonFiniteKnot { index } => write!(f, "Knot value t[{}] is not finite", index),
+            SplineError::NonFiniteCoefficient { index } => write!(f, "Coefficient value c[{}] is not finite", index),
+            SplineError::KnotMultiplicity { index, multiplicity, max } => 
+                write!(f, "Knot value t[{}] has multiplicity {}, but at most {} is allowed", index, multiplicity, max),
+            SplineError::EmptyDomain => write!(f, "The domain of the spline curve has zero length"),
+            SplineError::Invalid(errors) => {
+                write!(f, "Invalid spline curve: ")?;
+                for (i, e) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", e)?;
+                }
+                Ok(())
+            }
+            SplineError::UnsortedParameters { index } => 
+                write!(f, "Parameter values should be sorted in increasing order, but u[{}] is not", index),
+            SplineError::OutOfDomain { u, distance } => 
+                write!(f, "Parameter value {} is outside the domain of the spline curve, by {}", u, distance),
+            SplineError::KeyNotFound(key) => write!(f, "Key \"{}\" not found", key),
+            SplineError::DegreeMismatch { expected, got } => 
+                write!(f, "Expected a {}-degree spline curve, got degree {}", expected, got),
+            SplineError::DimensionMismatch { expected, got } => 
+                write!(f, "Expected a {}-dimensional spline curve, got dimension {}", expected, got),
+            SplineError::ZeroDegree => write!(f, "Not possible for a 0-degree spline curve"),
+            SplineError::ZeroDimension => write!(f, "Spline curve dimension should be at least 1"),
+            SplineError::Gap { index, distance } => 
+                write!(f, "Gap of {} between the end of a curve and the start of curve {}", distance, index),
+            SplineError::ParameterGap { index, distance } => 
+                write!(f, "Domain of curve {} starts {} away from the end of the previous curve", index, distance),
+            SplineError::NoCurves => write!(f, "No curves to concatenate"),
+            SplineError::SingularSystem => write!(f, "Singular system of linear equations"),
+            SplineError::ToleranceExceeded { tolerance, error } => 
+                write!(f, "Approximation error {} exceeds the tolerance {}", error, tolerance),
+            SplineError::NonFiniteInput { value } => write!(f, "Input value {} is not finite", value),
+            SplineError::InvalidAxis => write!(f, "Rotation axis should be finite, and not zero"),
+            SplineError::Plot(e) => write!(f, "Plot error: {}", e),
+            SplineError::Json(e) => write!(f, "JSON error: {}", e),
+            SplineError::Io(e) => write!(f, "IO error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SplineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SplineError::Json(e) => Some(e),
+            SplineError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for SplineError {
+    fn from(e: serde_json::Error) -> Self {
+        SplineError::Json(e)
+    }
+}
+
+impl From<std::io::Error> for SplineError {
+    fn from(e: std::io::Error) -> Self {
+        SplineError::Io(e)
+    }
+}
+
+#[cfg(feature = "plot")]
+impl<E: std::error::Error + Send + Sync> From<plotters::drawing::DrawingAreaErrorKind<E>> for SplineError {
+    fn from(e: plotters::drawing::DrawingAreaErrorKind<E>) -> Self {
+        SplineError::Plot(e.to_string())
+    }
+}
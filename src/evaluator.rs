@@ -0,0 +1,92 @@
+use super::{Result, Side, SplineCurve, SplineError};
+
+/**
+ * Cursor for evaluating a spline curve at single parameter values
+ * 
+ * An evaluator borrows a spline curve, and holds the knot interval of the last evaluation, and a work array for
+ * de Boor's algorithm, so that no memory is allocated when evaluating points. As the curve itself is not changed,
+ * a single curve can be shared by many threads, each using their own evaluator.
+ */
+#[derive(Debug, Clone)]
+pub struct Evaluator<'a, const K: usize, const N: usize> {
+    s: &'a SplineCurve<K, N>,
+    i: usize, // current interval, to speed up multiple evaluations
+    d: Vec<f64>, // de Boor work array, reused for multiple evaluations
+    side: Side, // side used at knots, for discontinuous derivatives
+}
+
+impl<'a, const K: usize, const N: usize> Evaluator<'a, K, N> {
+    /// Evaluator for a spline curve, checking once that its number of coefficients matches its knots, as the
+    /// curve can not be changed while it is borrowed.
+    pub fn new(s: &'a SplineCurve<K, N>) -> Result<Self> {
+        s.spline_ref().check_coefficients()?;
+        Ok(Self { s, i: K, d: vec![0.0; K + 1], side: Side::Right })
+    }
+
+    /// Sets the side from which the curve is evaluated at knots, see [Side].
+    pub fn with_side(mut self, side: Side) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// The spline curve evaluated by this evaluator.
+    pub fn spline_curve(&self) -> &'a SplineCurve<K, N> {
+        self.s
+    }
+
+    /// Calculates the spline coordinates for a single parameter value
+    /// 
+    /// The N coordinates of the point are returned as an array, for example [x, y] for a two-dimensional curve.
+    /// The knot interval found is kept, and is used as a starting point for the next evaluation, which speeds up
+    /// evaluations for increasing parameter values; for other values the interval is found by a binary search.
+    /// Parameter values outside the domain of the curve are handled according to the curve's
+    /// [extrapolation][SplineCurve::with_extrapolation] method, and only for [Extrapolate::Error][crate::Extrapolate::Error]
+    /// an [OutOfDomain][SplineError::OutOfDomain] error is returned, containing the distance to the nearest end of the domain.
+    pub fn eval(&mut self, t: f64) -> Result<[f64; N]> {
+        self.eval_derivative(t, 0)
+    }
+
+    /// Calculates the derivative of order `order` for a single parameter value, see [eval][Evaluator::eval].
+    pub fn eval_derivative(&mut self, t: f64, order: usize) -> Result<[f64; N]> {
+        let mut v = [0.0; N];
+        self.s.spline_ref().with_side(self.side).evaluate_point(t, order, order, &mut self.i, &mut self.d, &mut v)
+            .map_err(|distance| SplineError::OutOfDomain { u: t, distance })?;
+        Ok(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{SplineCurve, SplineError};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn evaluators_in_threads() {
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 0.3, 0.5, 1.0, 1.0, 1.0, 1.0],
+            vec![0.0, 0.5, 1.0, 3.0, 2.5, 1.0, 2.0, -3.0, 3.0, -3.0, 0.0, 1.0],
+        );
+        let u: Vec<f64> = (0..=100).map(|j| j as f64 / 100.0).collect();
+        let xy = s.evaluate(&u).unwrap();
+        let dxy = s.evaluate_derivative(&u, 1).unwrap();
+
+        std::thread::scope(|scope| {
+            for start in 0..4 {
+                let (s, u, xy, dxy) = (&s, &u, &xy, &dxy);
+                scope.spawn(move || {
+                    let mut e = s.evaluator().unwrap();
+                    for j in (start..u.len()).step_by(4) {
+                        assert_abs_diff_eq!(e.eval(u[j]).unwrap()[..], xy[2 * j..2 * j + 2], epsilon = 1E-12);
+                        assert_abs_diff_eq!(e.eval_derivative(u[j], 1).unwrap()[..], dxy[2 * j..2 * j + 2], epsilon = 1E-12);
+                    }
+                });
+            }
+        });
+    }
+
+    #[test]
+    fn too_few_coefficients() {
+        let s: SplineCurve<3, 1> = SplineCurve::new(vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0], vec![0.0, 1.0]);
+        assert!(matches!(s.evaluator(), Err(SplineError::TooFewCoefficients { required: 4, got: 2 })));
+    }
+}
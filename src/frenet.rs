@@ -0,0 +1,161 @@
+use super::{Result, SplineCurve};
+
+impl<const K: usize> SplineCurve<K, 2> {
+    /// Signed curvature of a two-dimensional curve, for a collection of parameter values
+    ///
+    /// The curvature is positive where the curve turns counter-clockwise, with increasing parameter values, and
+    /// negative where it turns clockwise; its absolute value is the inverse of the radius of the osculating circle.
+    /// For points where the first derivative is zero, the curvature is not defined, and NaN values are returned.
+    pub fn curvature(&self, u: &[f64]) -> Result<Vec<f64>> {
+        Ok(self.evaluate_with_derivatives(u, 2)?
+            .chunks(6)
+            .map(|p| (p[2] * p[5] - p[3] * p[4]) / (p[2] * p[2] + p[3] * p[3]).powf(1.5))
+            .collect())
+    }
+
+    /// Unit tangent vectors, in the direction of increasing parameter values, in the layout [tx0, ty0, tx1, ty1, ...].
+    pub fn tangent(&self, u: &[f64]) -> Result<Vec<f64>> {
+        Ok(self.evaluate_derivative(u, 1)?.chunks(2).flat_map(|d| normalize([d[0], d[1]])).collect())
+    }
+
+    /// Unit normal vectors, with the tangent vectors rotated counter-clockwise by 90 degrees, such that the
+    /// curvature vector is the signed curvature times the normal vector, in the layout [nx0, ny0, nx1, ny1, ...].
+    pub fn normal(&self, u: &[f64]) -> Result<Vec<f64>> {
+        Ok(self.tangent(u)?.chunks(2).flat_map(|t| [-t[1], t[0]]).collect())
+    }
+}
+
+/// Curvature, torsion, and the unit tangent, normal and binormal vectors of a three-dimensional curve at a point.
+struct Frenet {
+    curvature: f64,
+    torsion: f64,
+    tangent: [f64; 3],
+    normal: [f64; 3],
+    binormal: [f64; 3],
+}
+
+impl<const K: usize> SplineCurve<K, 3> {
+    /// Curvature of a three-dimensional curve, for a collection of parameter values
+    ///
+    /// The curvature is the inverse of the radius of the osculating circle, and is always positive, or zero for
+    /// straight parts of the curve. For points where the first derivative is zero, NaN values are returned.
+    pub fn curvature(&self, u: &[f64]) -> Result<Vec<f64>> {
+        Ok(self.frenet(u)?.iter().map(|f| f.curvature).collect())
+    }
+
+    /// Torsion of a three-dimensional curve, for a collection of parameter values
+    ///
+    /// The torsion measures how fast the curve twists out of its osculating plane, and is zero for planar curves.
+    /// It is not defined, and NaN values are returned, where the curvature is zero.
+    pub fn torsion(&self, u: &[f64]) -> Result<Vec<f64>> {
+        Ok(self.frenet(u)?.iter().map(|f| f.torsion).collect())
+    }
+
+    /// Unit tangent vectors, in the direction of increasing parameter values, in the layout [tx0, ty0, tz0, tx1, ...].
+    pub fn tangent(&self, u: &[f64]) -> Result<Vec<f64>> {
+        Ok(self.frenet(u)?.iter().flat_map(|f| f.tangent).collect())
+    }
+
+    /// Unit principal normal vectors, pointing towards the center of curvature, in the layout [nx0, ny0, nz0, nx1, ...]
+    ///
+    /// NaN values are returned where the curvature is zero.
+    pub fn normal(&self, u: &[f64]) -> Result<Vec<f64>> {
+        Ok(self.frenet(u)?.iter().flat_map(|f| f.normal).collect())
+    }
+
+    /// Unit binormal vectors, the cross products of the tangent and normal vectors, in the layout
+    /// [bx0, by0, bz0, bx1, ...]
+    ///
+    /// NaN values are returned where the curvature is zero.
+    pub fn binormal(&self, u: &[f64]) -> Result<Vec<f64>> {
+        Ok(self.frenet(u)?.iter().flat_map(|f| f.binormal).collect())
+    }
+
+    /// Frenet-Serret frames, from the first three derivatives of the curve.
+    fn frenet(&self, u: &[f64]) -> Result<Vec<Frenet>> {
+        Ok(self.evaluate_with_derivatives(u, 3)?
+            .chunks(12)
+            .map(|p| {
+                let (d1, d2, d3) = ([p[3], p[4], p[5]], [p[6], p[7], p[8]], [p[9], p[10], p[11]]);
+                let c = cross(d1, d2);
+                let (speed, lc) = (length(d1), length(c));
+                let tangent = normalize(d1);
+                let binormal = normalize(c);
+                Frenet {
+                    curvature: lc / speed.powi(3),
+                    torsion: (c[0] * d3[0] + c[1] * d3[1] + c[2] * d3[2]) / (lc * lc),
+                    tangent,
+                    normal: cross(binormal, tangent),
+                    binormal,
+                }
+            })
+            .collect())
+    }
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn length<const N: usize>(v: [f64; N]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+/// Unit vector in the direction of `v`, with NaN values for a zero vector.
+fn normalize<const N: usize>(v: [f64; N]) -> [f64; N] {
+    let l = length(v);
+    v.map(|x| x / l)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SplineCurve;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn curvature_2d() {
+        // parabola y=x^2, with x=2u-1
+        let s: SplineCurve<2, 2> = SplineCurve::new(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0], vec![-1.0, 0.0, 1.0, 1.0, -1.0, 1.0]);
+        let u = [0.0, 0.25, 0.5, 1.0];
+        let k: Vec<f64> = u.iter().map(|u| { let x: f64 = 2.0 * u - 1.0; 2.0 / (1.0 + 4.0 * x * x).powf(1.5) }).collect();
+        assert_abs_diff_eq!(s.curvature(&u).unwrap()[..], k[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.reversed().curvature(&[0.5]).unwrap()[0], -2.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.tangent(&[0.5]).unwrap()[..], [1.0, 0.0][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.normal(&[0.5, 1.0]).unwrap()[..], [0.0, 1.0, -2.0 / 5f64.sqrt(), 1.0 / 5f64.sqrt()][..], epsilon = 1E-12);
+    }
+
+    #[test]
+    fn frenet_3d() {
+        // twisted cubic (u, u^2, u^3)
+        let s: SplineCurve<3, 3> = SplineCurve::from_control_points(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+            vec![[0.0, 0.0, 0.0], [1.0 / 3.0, 0.0, 0.0], [2.0 / 3.0, 1.0 / 3.0, 0.0], [1.0, 1.0, 1.0]],
+        );
+        let u: [f64; 3] = [0.0, 0.5, 1.0];
+        let k: Vec<f64> = u.iter().map(|u| {
+            let (u2, u4) = (u * u, u * u * u * u);
+            2.0 * (1.0 + 9.0 * u2 + 9.0 * u4).sqrt() / (1.0 + 4.0 * u2 + 9.0 * u4).powf(1.5)
+        }).collect();
+        let t: Vec<f64> = u.iter().map(|u| 3.0 / (1.0 + 9.0 * u * u + 9.0 * u * u * u * u)).collect();
+        assert_abs_diff_eq!(s.curvature(&u).unwrap()[..], k[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.torsion(&u).unwrap()[..], t[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.tangent(&u[..1]).unwrap()[..], [1.0, 0.0, 0.0][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.normal(&u[..1]).unwrap()[..], [0.0, 1.0, 0.0][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.binormal(&u[..1]).unwrap()[..], [0.0, 0.0, 1.0][..], epsilon = 1E-12);
+
+        // frames are orthonormal
+        let (t, n, b) = (s.tangent(&u).unwrap(), s.normal(&u).unwrap(), s.binormal(&u).unwrap());
+        for j in 0..3 {
+            let dot = |a: &[f64], b: &[f64]| (0..3).map(|i| a[3 * j + i] * b[3 * j + i]).sum::<f64>();
+            assert_abs_diff_eq!([dot(&t, &t), dot(&n, &n), dot(&b, &b)][..], [1.0, 1.0, 1.0][..], epsilon = 1E-12);
+            assert_abs_diff_eq!([dot(&t, &n), dot(&n, &b), dot(&b, &t)][..], [0.0, 0.0, 0.0][..], epsilon = 1E-12);
+        }
+
+        // planar curves have zero torsion, and undefined frames on straight lines
+        let s: SplineCurve<2, 3> = SplineCurve::new(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0], vec![0.0, 1.0, 2.0, 0.0, 2.0, 0.0, 1.0, 1.0, 1.0]);
+        assert_abs_diff_eq!(s.torsion(&u).unwrap()[..], [0.0, 0.0, 0.0][..], epsilon = 1E-12);
+        let s: SplineCurve<1, 3> = SplineCurve::new(vec![0.0, 0.0, 1.0, 1.0], vec![0.0, 1.0, 0.0, 2.0, 0.0, 2.0]);
+        assert_eq!(s.curvature(&u).unwrap(), vec![0.0, 0.0, 0.0]);
+        assert!(s.normal(&u).unwrap().iter().all(|x| x.is_nan()));
+    }
+}
@@ -0,0 +1,266 @@
+use super::spline::SplineRef;
+use super::{Result, SplineError};
+
+/// Continuity at the joints of concatenated spline curves, see [JoinOptions]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Continuity {
+    /// Positional continuity: the end and start points of consecutive curves are moved to their midpoint.
+    #[default]
+    C0,
+    /// Continuous first derivatives: also the control points next to a joint are moved, such that the first
+    /// derivatives at both sides of the joint are equal to their average.
+    C1,
+    /// Continuous tangent directions: as [Continuity::C1], but only the directions of the first derivatives are
+    /// averaged, keeping their lengths.
+    G1,
+}
+
+/**
+ * Options for concatenating spline curves, see [SplineCurve::concatenate][crate::SplineCurve::concatenate]
+ *
+ * By default, curves are reparametrized, joined with [Continuity::C0], and gaps between the end and start points of
+ * consecutive curves larger than `1E-9` are reported as errors.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JoinOptions {
+    pub reparametrize: bool, // Shift parameter values, to start each curve at the end of the previous curve
+    pub continuity: Continuity, // Continuity at the joints
+    pub tolerance: f64, // Largest allowed distance between end and start points
+}
+
+impl Default for JoinOptions {
+    fn default() -> Self {
+        Self { reparametrize: true, continuity: Continuity::C0, tolerance: 1E-9 }
+    }
+}
+
+impl JoinOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets reparametrization: if `false`, the domain of each curve should start at the end of the domain of the
+    /// previous curve.
+    pub fn with_reparametrization(mut self, reparametrize: bool) -> Self {
+        self.reparametrize = reparametrize;
+        self
+    }
+
+    /// Sets the continuity at the joints, see [Continuity].
+    pub fn with_continuity(mut self, continuity: Continuity) -> Self {
+        self.continuity = continuity;
+        self
+    }
+
+    /// Sets the largest distance allowed between the end and start points of consecutive curves.
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+}
+
+/// Knots and coefficients of concatenated spline curves, with the same degree and dimension, see
+/// [SplineCurve::concatenate][crate::SplineCurve::concatenate].
+pub(crate) fn concatenate(curves: &[SplineRef<'_>], options: JoinOptions) -> Result<(Vec<f64>, Vec<f64>)> {
+    let (first, rest) = curves.split_first().ok_or(SplineError::NoCurves)?;
+    let (k, n) = (first.k, first.n);
+    if k == 0 {
+        return Err(SplineError::ZeroDegree);
+    }
+
+    // knots, and coefficients for each dimension, of the joined curve
+    let (mut t, c) = piece(first, usize::from(!rest.is_empty()), options.continuity)?;
+    let mut cs: Vec<Vec<f64>> = c.chunks(c.len() / n).map(|c| c.to_vec()).collect();
+
+    for (index, curve) in rest.iter().enumerate().map(|(i, s)| (i + 1, s)) {
+        if curve.k != k {
+            return Err(SplineError::DegreeMismatch { expected: k, got: curve.k });
+        } else if curve.n != n {
+            return Err(SplineError::DimensionMismatch { expected: n, got: curve.n });
+        }
+        let (mut tp, cp) = piece(curve, 1 + usize::from(index < rest.len()), options.continuity)?;
+        let end = t[t.len() - 1];
+        let shift = end - tp[k];
+        if options.reparametrize {
+            tp.iter_mut().for_each(|x| *x += shift);
+        } else if shift != 0.0 {
+            return Err(SplineError::ParameterGap { index, distance: -shift });
+        }
+        let mut cps: Vec<Vec<f64>> = cp.chunks(cp.len() / n).map(|c| c.to_vec()).collect();
+
+        let distance = cs.iter().zip(cps.iter()).map(|(a, b)| (a[a.len() - 1] - b[0]).powi(2)).sum::<f64>().sqrt();
+        if distance > options.tolerance {
+            return Err(SplineError::Gap { index, distance });
+        }
+
+        // first derivatives at the joint, from the last and first two control points
+        let (h1, h2) = (end - t[t.len() - k - 2], tp[k + 1] - end);
+        let d1: Vec<f64> = cs.iter().map(|c| k as f64 * (c[c.len() - 1] - c[c.len() - 2]) / h1).collect();
+        let d2: Vec<f64> = cps.iter().map(|c| k as f64 * (c[1] - c[0]) / h2).collect();
+        let (l1, l2) = (norm(&d1), norm(&d2));
+        let d: Option<(Vec<f64>, Vec<f64>)> = match options.continuity {
+            Continuity::C0 => None,
+            Continuity::C1 => {
+                let d: Vec<f64> = d1.iter().zip(d2.iter()).map(|(a, b)| 0.5 * (a + b)).collect();
+                Some((d.clone(), d))
+            }
+            Continuity::G1 => {
+                let dir: Vec<f64> = d1.iter().zip(d2.iter())
+                    .map(|(a, b)| if l1 > 0.0 { a / l1 } else { 0.0 } + if l2 > 0.0 { b / l2 } else { 0.0 })
+                    .collect();
+                let l = norm(&dir);
+                if l > 0.0 {
+                    Some((dir.iter().map(|x| x * l1 / l).collect(), dir.iter().map(|x| x * l2 / l).collect()))
+                } else {
+                    None
+                }
+            }
+        };
+
+        for dim in 0..n {
+            let (c, cp) = (&mut cs[dim], &mut cps[dim]);
+            let m = c.len() - 1;
+            let p = 0.5 * (c[m] + cp[0]);
+            c[m] = p;
+            if let Some((d1, d2)) = &d {
+                c[m - 1] = p - d1[dim] * h1 / k as f64;
+                cp[1] = p + d2[dim] * h2 / k as f64;
+            }
+            c.extend_from_slice(&cp[1..]);
+        }
+        // the joint knot gets multiplicity k
+        t.pop();
+        t.extend_from_slice(&tp[k + 1..]);
+    }
+    Ok((t, cs.concat()))
+}
+
+/// Knots and coefficients of a clamped curve to be joined at `joints` ends
+/// 
+/// For [Continuity::C1] and [Continuity::G1], the control points next to each joint are moved, and these should not
+/// be shared with another joint, or be an end point of the joined curve. For curves with too few control points,
+/// such as Bézier curves of degree one or two, knots are inserted in the middle of the widest knot intervals, which
+/// does not change the curve.
+fn piece(curve: &SplineRef<'_>, joints: usize, continuity: Continuity) -> Result<(Vec<f64>, Vec<f64>)> {
+    let (mut t, mut c) = curve.clamped()?;
+    if continuity == Continuity::C0 || joints == 0 {
+        return Ok((t, c));
+    }
+    let k = curve.k;
+    while c.len() / curve.n < joints + 2 {
+        let j = (k..t.len() - k - 1).max_by(|&i, &j| (t[i + 1] - t[i]).total_cmp(&(t[j + 1] - t[j]))).unwrap();
+        let (ti, ci) = SplineRef { t: &t, c: &c, ..*curve }.insert_knot(0.5 * (t[j] + t[j + 1]), 1)?;
+        t = ti;
+        c = ci;
+    }
+    Ok((t, c))
+}
+
+fn norm(v: &[f64]) -> f64 {
+    v.iter().map(|x| x * x).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Continuity, JoinOptions};
+    use crate::{SplineCurve, SplineError};
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn concatenate() {
+        // quadratic curve, with a double interior knot
+        let s: SplineCurve<2, 2> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 1.0, 2.0, 2.0, 3.0, 3.0, 3.0],
+            vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 0.0, 2.0, 1.0, -1.0, 0.0, 1.0],
+        );
+        let u: Vec<f64> = (0..=30).map(|i| i as f64 * 0.1).collect();
+
+        // split curves are joined again, without changes
+        let (s1, s2) = s.split_at(1.5).unwrap();
+        let j = SplineCurve::concatenate(&[s1.clone(), s2.clone()], JoinOptions::new().with_reparametrization(false)).unwrap();
+        assert_eq!(j.t, vec![0.0, 0.0, 0.0, 1.0, 1.5, 1.5, 2.0, 2.0, 3.0, 3.0, 3.0]);
+        assert_abs_diff_eq!(j.evaluate(&u).unwrap()[..], s.evaluate(&u).unwrap()[..], epsilon = 1E-12);
+        let j = SplineCurve::concatenate(&[s1.clone(), s2.clone()], JoinOptions::new().with_continuity(Continuity::C1)).unwrap();
+        assert_abs_diff_eq!(j.evaluate(&u).unwrap()[..], s.evaluate(&u).unwrap()[..], epsilon = 1E-12);
+
+        // reparametrized, with the second curve shifted to start at the end of the first curve
+        let j = SplineCurve::concatenate(&[s.clone(), s.clone()], JoinOptions::new().with_tolerance(10.0)).unwrap();
+        assert_eq!(j.domain(), (0.0, 6.0));
+        let ends = j.evaluate(&[0.0, 3.0, 6.0]).unwrap();
+        assert_abs_diff_eq!(ends[..], [0.0, 0.0, 2.5, 0.5, 5.0, 1.0][..], epsilon = 1E-12);
+
+        // continuous first derivatives, or only tangent directions, at the joint
+        for continuity in [Continuity::C1, Continuity::G1] {
+            let j = SplineCurve::concatenate(&[s.clone(), s.clone()], JoinOptions::new().with_tolerance(10.0).with_continuity(continuity)).unwrap();
+            let dl = j.evaluate_limit(&[3.0], 1, crate::Side::Left).unwrap();
+            let dr = j.evaluate_limit(&[3.0], 1, crate::Side::Right).unwrap();
+            let cross = dl[0] * dr[1] - dl[1] * dr[0];
+            assert_abs_diff_eq!(cross, 0.0, epsilon = 1E-10);
+            if continuity == Continuity::C1 {
+                assert_abs_diff_eq!(dl[..], dr[..], epsilon = 1E-10);
+            }
+        }
+
+        assert!(matches!(SplineCurve::concatenate(&[s.clone(), s.clone()], JoinOptions::new()), Err(SplineError::Gap { index: 1, .. })));
+        assert!(matches!(
+            SplineCurve::concatenate(&[s1, s2.clone(), s2], JoinOptions::new().with_reparametrization(false)),
+            Err(SplineError::ParameterGap { index: 2, .. })
+        ));
+        assert!(matches!(SplineCurve::<2, 2>::concatenate(&[], JoinOptions::new()), Err(SplineError::NoCurves)));
+
+        // unclamped curves are clamped before joining
+        let s: SplineCurve<2, 2> = SplineCurve::new(
+            (0..9).map(|v| v as f64).collect(),
+            vec![1.0, 3.0, 2.0, 4.0, 0.0, 1.0, 0.0, 1.0, 3.0, 2.0, 1.0, 0.0],
+        );
+        let u: Vec<f64> = (20..=60).map(|i| i as f64 * 0.1).collect();
+        let (s1, s2) = s.split_at(4.0).unwrap();
+        let j = SplineCurve::concatenate(&[s1, s2], JoinOptions::new().with_reparametrization(false)).unwrap();
+        assert!(j.is_clamped());
+        assert_eq!(j.domain(), (2.0, 6.0));
+        assert_abs_diff_eq!(j.evaluate(&u).unwrap()[..], s.evaluate(&u).unwrap()[..], epsilon = 1E-12);
+        let j = SplineCurve::concatenate(&[s.clone(), s.reversed()], JoinOptions::new()).unwrap();
+        assert_eq!(j.domain(), (2.0, 10.0));
+        assert_abs_diff_eq!(j.evaluate(&[10.0]).unwrap()[..], s.evaluate(&[2.0]).unwrap()[..], epsilon = 1E-12);
+    }
+
+    #[test]
+    fn short_pieces() {
+        // quadratic Bézier curves, sharing the control points next to both joints of the middle curve
+        let pieces: Vec<SplineCurve<2, 2>> = [[0.0, 1.0, 2.0, 0.0, 1.0, 0.0], [2.0, 3.0, 4.0, 0.0, -1.0, 0.0], [4.0, 5.0, 6.0, 0.0, 2.0, 0.0]]
+            .iter()
+            .map(|c| SplineCurve::new(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0], c.to_vec()))
+            .collect();
+        // polyline, with degree one curves having only their end points as control points
+        let lines: Vec<SplineCurve<1, 2>> = [[0.0, 1.0, 0.0, 0.0], [1.0, 1.0, 0.0, 1.0], [1.0, 3.0, 1.0, 1.0], [3.0, 3.0, 1.0, 0.0]]
+            .iter()
+            .map(|c| SplineCurve::new(vec![0.0, 0.0, 1.0, 1.0], c.to_vec()))
+            .collect();
+
+        for continuity in [Continuity::C1, Continuity::G1] {
+            let options = JoinOptions::new().with_continuity(continuity);
+            let j = SplineCurve::concatenate(&pieces, options).unwrap();
+            assert_abs_diff_eq!(j.evaluate(&[0.0, 3.0]).unwrap()[..], [0.0, 0.0, 6.0, 0.0][..], epsilon = 1E-12);
+            for u in [1.0, 2.0] {
+                let dl = j.evaluate_limit(&[u], 1, crate::Side::Left).unwrap();
+                let dr = j.evaluate_limit(&[u], 1, crate::Side::Right).unwrap();
+                assert_abs_diff_eq!(dl[0] * dr[1] - dl[1] * dr[0], 0.0, epsilon = 1E-12);
+                if continuity == Continuity::C1 {
+                    assert_abs_diff_eq!(dl[..], dr[..], epsilon = 1E-12);
+                }
+            }
+
+            let j = SplineCurve::concatenate(&lines, options).unwrap();
+            assert_eq!(j.domain(), (0.0, 4.0));
+            assert_abs_diff_eq!(j.evaluate(&[0.0, 4.0]).unwrap()[..], [0.0, 0.0, 3.0, 0.0][..], epsilon = 1E-12);
+            for u in [1.0, 2.0, 3.0] {
+                let dl = j.evaluate_limit(&[u], 1, crate::Side::Left).unwrap();
+                let dr = j.evaluate_limit(&[u], 1, crate::Side::Right).unwrap();
+                assert_abs_diff_eq!(dl[0] * dr[1] - dl[1] * dr[0], 0.0, epsilon = 1E-12);
+                if continuity == Continuity::C1 {
+                    assert_abs_diff_eq!(dl[..], dr[..], epsilon = 1E-12);
+                }
+            }
+        }
+    }
+}
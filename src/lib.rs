@@ -1,6 +1,9 @@
 #![doc = include_str!("../README.md")]
 
-pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+pub type Result<T> = std::result::Result<T, SplineError>;
+
+pub mod error;
+pub use error::SplineError;
 
 
 pub type LinearSpline = spline::SplineCurve::<1,1>;
@@ -16,6 +19,20 @@ pub type QuinticSpline3D = spline::SplineCurve::<5,3>;
 pub mod spline;
 pub use spline::*;
 
+pub mod evaluator;
+pub use evaluator::*;
+
+pub mod arclength;
+pub use arclength::*;
+
+mod frenet;
+
+pub mod dynspline;
+pub use dynspline::*;
+
+pub mod join;
+pub use join::*;
+
 pub mod splines;
 pub use splines::*;
 
@@ -1,26 +1,23 @@
 
 
-use super::{Result, SplineCurve};
+use super::{DynSplineCurve, Result, SplineError};
 use plotters::prelude::*;
 use std::iter::repeat;
 
 
-fn range_knots<const K:usize, const N:usize>(s: &SplineCurve<K,N>) -> Result<[f64;4]> {
+fn range_knots(s: &DynSplineCurve) -> Result<[f64;4]> {
     let nc = s.c.len();
     let nc_2 = nc/2;
-    let (x_min, x_max) = match N {
-        1 => (
-            s.t.iter().cloned().reduce(f64::min).unwrap(), 
-            s.t.iter().cloned().reduce(f64::max).unwrap()
-        ),
+    let (x_min, x_max) = match s.n {
+        1 => s.domain(),
         2 => (
             s.c[0..nc_2].iter().cloned().reduce(f64::min).unwrap(),
             s.c[0..nc_2].iter().cloned().reduce(f64::max).unwrap(),
         ),
-        _ => return Err("Only one and two dimensional curve splnies supported".into())
+        _ => return Err(SplineError::Plot("Only one and two dimensional curve splines supported".to_string()))
 
     };
-    let (y_min, y_max) = match N {
+    let (y_min, y_max) = match s.n {
         1 => (
             s.c[0..nc].iter().cloned().reduce(f64::min).unwrap(),
             s.c[0..nc].iter().cloned().reduce(f64::max).unwrap(),
@@ -29,7 +26,7 @@ fn range_knots<const K:usize, const N:usize>(s: &SplineCurve<K,N>) -> Result<[f6
             s.c[nc_2..nc].iter().cloned().reduce(f64::min).unwrap(),
             s.c[nc_2..nc].iter().cloned().reduce(f64::max).unwrap(),
         ),
-        _ => return Err("Only one and two dimensional curve splnies supported".into())
+        _ => return Err(SplineError::Plot("Only one and two dimensional curve splines supported".to_string()))
     };
     Ok([x_min, x_max, y_min, y_max])
 }
@@ -46,7 +43,7 @@ fn range_spline(u: &[f64], xn: &[f64]) -> Result<[f64;4]> {
             xn.iter().step_by(1).cloned().reduce(f64::min).unwrap(),
             xn.iter().step_by(1).cloned().reduce(f64::max).unwrap(),
         ),
-        _ => return Err("only dimensions 0 and 1 supported".into())
+        _ => return Err(SplineError::Plot("Only one and two dimensional curve splines supported".to_string()))
     };
     let (y_min, y_max) = match n {
         1 => (
@@ -57,7 +54,7 @@ fn range_spline(u: &[f64], xn: &[f64]) -> Result<[f64;4]> {
             xn.iter().skip(1).step_by(1).cloned().reduce(f64::min).unwrap(),
             xn.iter().skip(1).step_by(1).cloned().reduce(f64::max).unwrap(),
         ),
-        _ => return Err("only dimensions 0 and 1 supported".into())
+        _ => return Err(SplineError::Plot("Only one and two dimensional curve splines supported".to_string()))
     };
     Ok([x_min, x_max, y_min, y_max])
 }
@@ -66,8 +63,8 @@ fn range_spline(u: &[f64], xn: &[f64]) -> Result<[f64;4]> {
 
 
 /// Plots a two-dimensional (xy) spline curve for testing review
-pub(crate) fn plot_base<const K: usize, const N: usize>(
-    s: SplineCurve<K,N>,
+pub(crate) fn plot_base(
+    s: &DynSplineCurve,
     filepath: &str,
     wxh: (u32, u32),
     u: Option<&[f64]>,
@@ -80,24 +77,22 @@ pub(crate) fn plot_base<const K: usize, const N: usize>(
     let uv: Vec<f64>;
 
     let u = u.unwrap_or({
-        let n = s.t.len();
-        let tb = s.t[0];
-        let te = s.t[n-1];
+        let (tb, te) = s.domain();
         let ts = (te-tb)/(M-1) as f64;
         uv = repeat(ts).take(M).scan(tb, |s, x| { let t= *s;  *s+=x; Some(t)}).collect();
         &uv
     });
 
-    let s_xy = s.evaluate(&u)?;
+    let s_xy = s.evaluate(u)?;
 
-    let [x_min, x_max, y_min, y_max] = match N {
+    let [x_min, x_max, y_min, y_max] = match s.n {
            1 => range_spline(u, &s_xy)?,
            2 => if plot_control_points {
-                    range_knots(&s)?
+                    range_knots(s)?
                 } else {
                     range_spline(u, &s_xy)?
                 },
-           _ => return Err("only 2D plots supported".into())
+           _ => return Err(SplineError::Plot("Only one and two dimensional curve splines supported".to_string()))
         };
 
     let width = x_max - x_min;
@@ -123,11 +118,11 @@ pub(crate) fn plot_base<const K: usize, const N: usize>(
     // draw the mesh
     chart.configure_mesh()
         .x_labels(10)
-        .x_label_formatter(&|v| if v>=&300E-9 && v<=&1E-6 {format!("{}", v*1E9)} else {format!("{}", v)})
+        .x_label_formatter(&|v| if (300E-9..=1E-6).contains(v) {format!("{}", v*1E9)} else {format!("{}", v)})
         .label_style(TextStyle::from(("sans-serif", 20).into_font()))
         .draw()?;
 
-    if N==2 && plot_control_points {
+    if s.n==2 && plot_control_points {
         // draw control points, only in 2D case, and if requested
         let nc = s.c.len();
         let nc_2 = nc/2;
@@ -143,11 +138,11 @@ pub(crate) fn plot_base<const K: usize, const N: usize>(
     }
 
     // draw spline 
-    if N==2 {
+    if s.n==2 {
         chart.draw_series(LineSeries::new(
             s_xy.chunks(2).map(|xy|(xy[0],xy[1])),
             spline_color.mix(1.0).stroke_width(5)))?;
-    } else if N ==1 {
+    } else if s.n ==1 {
         chart.draw_series(LineSeries::new(
             u.iter().zip(s_xy.iter()).map(|(&x,&y)|(x,y)),
             spline_color.mix(1.0).stroke_width(4)))?;
@@ -155,9 +150,9 @@ pub(crate) fn plot_base<const K: usize, const N: usize>(
     }
 
     // xy value target of fit
-    if xy.is_some() {
+    if let Some(xy) = xy {
         chart.draw_series(LineSeries::new(
-            xy.unwrap().chunks(2).map(|xy|(xy[0],xy[1])),
+            xy.chunks(2).map(|xy|(xy[0],xy[1])),
             BLACK.mix(1.0).stroke_width(2),
         ))?;
     }   
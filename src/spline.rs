@@ -1,59 +1,206 @@
-use super::Result;
+use super::{ArcLengthEvaluator, DynSplineCurve, Evaluator, JoinOptions, Result, SplineError};
 #[cfg(feature="plot")]
 use super::plot::plot_base;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
 
 
-const DE_BOOR_SIZE: usize = 6 ;
-
 /**
  * General B-Spline Curve Knot/Coefficient Representation
  */
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "DynSplineCurve")]
 pub struct SplineCurve<const K: usize, const N: usize> {
     pub t: Vec<f64>, // Knot values
     pub c: Vec<f64>, // b-Spline coefficients
     pub k: usize,        // Spline degree
     pub n: usize,        // Spline dimension
-    i: Option<usize>, // current interval, to speed up multiple evaluations
+    #[serde(default, skip_serializing_if = "Extrapolate::is_clamp")]
+    pub extrapolate: Extrapolate, // Evaluation outside the domain t[k]..=t[n-k-1]
 }
 
 impl<const K: usize, const N: usize> SplineCurve<K, N> {
     pub fn new(t: Vec<f64>, c: Vec<f64>) -> Self {
-        Self { t, c, k: K, n: N, i: None }
+        Self { t, c, k: K, n: N, extrapolate: Extrapolate::Clamp }
+    }
+
+    /// Sets the extrapolation method, used for all evaluations outside the domain of the curve
+    /// 
+    /// By default, parameter values are clamped to the domain `t[k]..=t[n-k-1]`; see [Extrapolate] for the
+    /// other options.
+    pub fn with_extrapolation(mut self, extrapolate: Extrapolate) -> Self {
+        self.extrapolate = extrapolate;
+        self
+    }
+
+    pub(crate) fn spline_ref(&self) -> SplineRef<'_> {
+        SplineRef { t: &self.t, c: &self.c, k: K, n: N, extrapolate: self.extrapolate, side: Side::Right }
+    }
+
+    /// Creates a spline curve, checking its knots and coefficients, see [validate][SplineCurve::validate]
+    /// 
+    /// There is no limit on the degree of the curve, but a degree `K` curve needs at least `2(K+1)` knots,
+    /// and `t.len()-K-1` coefficients for each of its `N` dimensions.
+    pub fn try_new(t: Vec<f64>, c: Vec<f64>) -> Result<Self> {
+        let s = Self::new(t, c);
+        s.validate()?;
+        Ok(s)
+    }
+
+    /// Creates a spline curve from its knots and control points, given as points with `N` coordinates
+    /// 
+    /// The coefficients are stored dimension-major in `c`, with first all the x-coordinates, then all the
+    /// y-coordinates, and so on; this constructor does the conversion. As [new][SplineCurve::new], it does not check
+    /// the curve, see [validate][SplineCurve::validate].
+    pub fn from_control_points(t: Vec<f64>, points: Vec<[f64; N]>) -> Self {
+        let c = (0..N).flat_map(|dim| points.iter().map(move |p| p[dim])).collect();
+        Self::new(t, c)
+    }
+
+    /// Creates a closed, periodic, spline curve from the knots and control points of a single period
+    /// 
+    /// The knots `t[0]..=t[m]` span one period, with length `t[m]-t[0]`, and for each dimension `m` coefficients
+    /// are required, one for each knot interval. The knot vector is extended periodically by `K` knots at both ends,
+    /// and the first `K` coefficients are repeated at the end, such that the curve, and all its derivatives up to
+    /// order `K-1`, are continuous across the seam. The curve uses [Extrapolate::Periodic], wrapping all parameter
+    /// values into the domain `t[0]..=t[m]`, which is also used for serialization.
+    pub fn periodic(t: Vec<f64>, c: Vec<f64>) -> Result<Self> {
+        let (t, c) = periodic_extension(&t, &c, K, N)?;
+        Self::try_new(t, c).map(|s| s.with_extrapolation(Extrapolate::Periodic))
+    }
+
+    /// Number of control points, equal to the number of coefficients for each dimension
+    pub fn control_point_count(&self) -> usize {
+        self.c.len() / N
+    }
+
+    /// Iterator over the control points of the curve, as points with `N` coordinates
+    pub fn control_points(&self) -> impl Iterator<Item = [f64; N]> + '_ {
+        (0..self.control_point_count()).map(move |j| self.point(j))
+    }
+
+    /// Control point `j`, or `None` if the curve has no control point with this index
+    pub fn control_point(&self, j: usize) -> Option<[f64; N]> {
+        (j < self.control_point_count()).then(|| self.point(j))
+    }
+
+    /// Changes control point `j` into `p`, which changes the curve locally, within knots `t[j]..=t[j+K+1]`
+    /// 
+    /// For periodic curves, as created by [SplineCurve::periodic], the first `K` control points are repeated at the
+    /// end, and both copies are changed, keeping the curve closed without a seam. A curve is considered periodic if
+    /// it uses [Extrapolate::Periodic], and its knots and control points still have this periodic structure; this is
+    /// not the case after inserting a single knot, for example, and then only control point `j` is changed.
+    /// 
+    /// # Panics
+    /// If the curve has no control point with index `j`.
+    pub fn set_control_point(&mut self, j: usize, p: [f64; N]) {
+        let nc = self.control_point_count();
+        assert!(j < nc, "control point index {} out of range for a curve with {} control points", j, nc);
+        let copy = self.periodic_copy(j);
+        for (dim, x) in p.iter().enumerate() {
+            self.c[dim * nc + j] = *x;
+            if let Some(jc) = copy {
+                self.c[dim * nc + jc] = *x;
+            }
+        }
+    }
+
+    /// Index of the repeated copy of control point `j` of a periodic curve, or `None` if the curve does not have the
+    /// structure of [SplineCurve::periodic], or if control point `j` is not repeated
+    fn periodic_copy(&self, j: usize) -> Option<usize> {
+        let nc = self.control_point_count();
+        if self.extrapolate != Extrapolate::Periodic || K == 0 || nc <= K || self.t.len() != nc + K + 1 {
+            return None;
+        }
+        // period m, in control points, and in parameter values
+        let m = nc - K;
+        let t = &self.t;
+        let period = t[m + K] - t[K];
+        let tolerance = 1E-12 * period.abs().max(1.0);
+        let knots = (0..=2 * K).all(|i| (t[i + m] - t[i] - period).abs() <= tolerance);
+        let coefficients = (0..N).all(|dim| (0..K).all(|i| self.c[dim * nc + i] == self.c[dim * nc + i + m]));
+        if !knots || !coefficients {
+            None
+        } else if j < K {
+            Some(j + m)
+        } else if j >= m {
+            Some(j - m)
+        } else {
+            None
+        }
     }
 
-    pub fn try_new(t: Vec<f64>, c: Vec<f64>) -> std::result::Result<Self, Box<dyn std::error::Error>> {
-        let n = t.len();
-        let nc = c.len() / N;
-        if nc<(K+1) {
-            return Err(format!("Need at least {} coefficients to plot a {}-degree Spline curve", N*(n+K+1), K).into());
+    fn point(&self, j: usize) -> [f64; N] {
+        let nc = self.control_point_count();
+        let mut p = [0.0; N];
+        for (dim, x) in p.iter_mut().enumerate() {
+            *x = self.c[dim * nc + j];
         }
-        Ok(Self { t, c, k: K, n: N, i: None })
+        p
+    }
+
+    /// Checks all the invariants of the spline curve
+    /// 
+    /// As the fields of a spline curve are public, they can be changed after creation, and this function can be
+    /// used to check a curve before use. The invariants are: the degree `k` and dimension `n` fields should match `K`
+    /// and `N`, there should be at least `2(K+1)` knots, `N` times `t.len()-K-1` coefficients, all knots and
+    /// coefficients should be finite, knots should be non-decreasing with multiplicities of at most `K+1`, and
+    /// the domain `t[K]..=t[t.len()-K-1]` should not be empty. All violations are reported, in a
+    /// [SplineError::Invalid] error.
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = Vec::new();
+        if self.k != K {
+            errors.push(SplineError::DegreeMismatch { expected: K, got: self.k });
+        }
+        if self.n != N {
+            errors.push(SplineError::DimensionMismatch { expected: N, got: self.n });
+        }
+        errors.extend(self.spline_ref().validate());
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(SplineError::Invalid(errors))
+        }
+    }
+
+    /// Reads a spline curve from its JSON representation, as produced by [to_json][SplineCurve::to_json]
+    /// 
+    /// The degree `k` and dimension `n` in the JSON data have to match the `K` and `N` parameters of the spline
+    /// curve type, and the number of coefficients has to match the number of knots. These are reported as
+    /// [SplineError::DegreeMismatch], [SplineError::DimensionMismatch], and [SplineError::Invalid] errors, and only
+    /// malformed JSON data gives a [SplineError::Json] error.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Self::try_from(DynSplineCurve::from_json(json)?)
+    }
+
+    /// JSON representation of the spline curve, with its knots `t`, coefficients `c`, degree `k`, and dimension `n`.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
     }
 
     #[cfg(feature="plot")]
     pub fn plot(self, filepath: &str, wxh: (u32, u32)) -> Result<()> {
-        Ok(plot_base(self, filepath, wxh, None, None, false)?)
+        plot_base(&self.into(), filepath, wxh, None, None, false)
     }
 
     #[cfg(feature="plot")]
     pub fn plot_with_parameter(self, filepath: &str, wxh: (u32, u32), u:Option<&[f64]>) -> Result<()> {
-        Ok(plot_base(self, filepath, wxh, u, None, false)?)
+        plot_base(&self.into(), filepath, wxh, u, None, false)
     }
 
     #[cfg(feature="plot")]
     pub fn plot_with_control_points(self, filepath: &str, wxh: (u32, u32)) -> Result<()> {
-        Ok(plot_base(self, filepath, wxh, None, None, true)?)
+        plot_base(&self.into(), filepath, wxh, None, None, true)
     }
 
     #[cfg(feature="plot")]
     pub fn plot_with_data(self, filepath: &str, wxh: (u32, u32), xy: &[f64]) -> Result<()> {
-        Ok(plot_base(self, filepath, wxh, None, Some(xy), false)?)
+        plot_base(&self.into(), filepath, wxh, None, Some(xy), false)
     }
 
     #[cfg(feature="plot")]
     pub fn plot_with_control_points_and_data(self, filepath: &str, wxh: (u32, u32), xy: &[f64]) -> Result<()> {
-        Ok(plot_base(self, filepath, wxh, None, Some(xy), true)?)
+        plot_base(&self.into(), filepath, wxh, None, Some(xy), true)
     }
 
     /// Calulates spline coordinates for a collection of parameter values
@@ -62,119 +209,1167 @@ impl<const K: usize, const N: usize> SplineCurve<K, N> {
     /// followed by the coordinates of all the other points. For example, for a two-dimensional curve (N=2), the (x,y)-coordinates are given as
     /// [x0, y0, x1, y1, x2, ...] and for a three-dimensional curve, with coordinates (x,y,z) you will get [x0, y0, z0, x1, y1, z1, x2, y2 ...].
     /// If you need to convert them into individual coordinate arrays, I suggest to use the [transpose][crate::transpose] function.
+    /// 
+    /// The parameter values can be given in any order, and can contain duplicates, but evaluation is fastest for
+    /// increasing values. Values outside the domain of the curve are handled according to its
+    /// [extrapolation][SplineCurve::with_extrapolation] method.
     pub fn evaluate(&self, u: &[f64]) -> Result<Vec<f64>> {
-        let n = self.t.len();
-        let nc = self.c.len() / N;
-        if nc<(K+1) {
-            return Err(format!("Need at least {} coefficients to plot a {}-degree Spline curve", N*(n+K+1), K).into());
+        self.evaluate_base(u, 0, 0)
+    }
+
+    /// Calculates the derivatives of a given order for a collection of parameter values
+    /// 
+    /// The derivatives are with respect to the curve parameter, and are given in the same layout as the coordinates
+    /// produced by [evaluate][SplineCurve::evaluate]: for a two-dimensional curve and `order=1` you get
+    /// [dx0, dy0, dx1, dy1, ...]. An order of zero gives the curve coordinates, and orders larger than the spline degree
+    /// result in zero values.
+    pub fn evaluate_derivative(&self, u: &[f64], order: usize) -> Result<Vec<f64>> {
+        self.evaluate_base(u, order, order)
+    }
+
+    /// Calculates one-sided limits of the derivatives of a given order, with order zero giving the coordinates
+    /// 
+    /// The values are identical to those of [evaluate_derivative][SplineCurve::evaluate_derivative], except at knots
+    /// where the derivative is discontinuous, due to repeated knots; by default, evaluation uses the limits from the right.
+    pub fn evaluate_limit(&self, u: &[f64], order: usize, side: Side) -> Result<Vec<f64>> {
+        self.spline_ref().with_side(side).evaluate(u, order, order)
+    }
+
+    /// Calculates the spline coordinates, and their first `m` derivatives, for a collection of parameter values
+    /// 
+    /// For each parameter value, the N coordinates are followed by the N values of the first derivative, then those of the
+    /// second derivative, and so on, giving `(m+1)*N` values per point. For example, for a two-dimensional curve and `m=2`
+    /// the values are [x0, y0, dx0, dy0, ddx0, ddy0, x1, y1, ...].
+    pub fn evaluate_with_derivatives(&self, u: &[f64], m: usize) -> Result<Vec<f64>> {
+        self.evaluate_base(u, 0, m)
+    }
+
+    fn evaluate_base(&self, u: &[f64], order_start: usize, order_end: usize) -> Result<Vec<f64>> {
+        self.spline_ref().evaluate(u, order_start, order_end)
+    }
+
+
+    /// Evaluator for single parameter values
+    /// 
+    /// The evaluator holds the state used to speed up repeated evaluations, such as the current knot interval,
+    /// while the curve itself is only borrowed; use one evaluator for each thread to evaluate a curve concurrently.
+    /// An error is returned if the number of coefficients does not match the knots.
+    pub fn evaluator(&self) -> Result<Evaluator<'_, K, N>> {
+        Evaluator::new(self)
+    }
+
+    /// Domain of the curve, as start and end parameter values `(t[K], t[t.len()-K-1])`
+    /// 
+    /// The curve is only defined on its domain, and for parameter values outside the domain the
+    /// [extrapolation][SplineCurve::with_extrapolation] method is used. For a clamped knot vector, with the first and
+    /// last `K+1` knots equal, this is the range of the knots, but for unclamped, or open, knot vectors the first
+    /// and last `K` knots are outside of the domain.
+    pub fn domain(&self) -> (f64, f64) {
+        self.spline_ref().domain()
+    }
+
+    /// Checks if the knot vector is clamped, with the first and last `K+1` knots at the start and end of the domain
+    pub fn is_clamped(&self) -> bool {
+        self.spline_ref().is_clamped()
+    }
+
+    /// Converts a curve with an unclamped knot vector into the same curve with a clamped knot vector
+    /// 
+    /// The knots at the start and end of the domain are inserted until they have a multiplicity of `K+1`, after
+    /// which the knots outside the domain, and their coefficients, are removed. The curve is unchanged within
+    /// its domain, but as a clamped curve starts and ends at its first and last control points, and is
+    /// extrapolated differently when using [Extrapolate::Polynomial].
+    pub fn clamped(&self) -> Result<Self> {
+        let (t, c) = self.spline_ref().clamped()?;
+        Ok(Self::new(t, c).with_extrapolation(self.extrapolate))
+    }
+
+    /// Inserts the knot `u`, `multiplicity` times, using Boehm's algorithm
+    /// 
+    /// The shape of the curve does not change, but the curve gets one more control point, for each of its `N`
+    /// dimensions, for each inserted knot. The knot has to be within the domain of the curve, and its total
+    /// multiplicity can not exceed `K+1`. Inserting a knot until its multiplicity is `K` gives a control point on
+    /// the curve, at parameter value `u`.
+    pub fn insert_knot(&self, u: f64, multiplicity: usize) -> Result<Self> {
+        let (t, c) = self.spline_ref().insert_knot(u, multiplicity)?;
+        Ok(Self::new(t, c).with_extrapolation(self.extrapolate))
+    }
+
+    /// Inserts a collection of knots at once, using the Oslo algorithm
+    /// 
+    /// The knots can be given in any order, and can contain duplicates, and are merged with the current knots, without
+    /// changing the shape of the curve, similar to [insert_knot][SplineCurve::insert_knot]. Refining two curves
+    /// with each others knots gives them the same knot vector.
+    pub fn refine(&self, u: &[f64]) -> Result<Self> {
+        let (t, c) = self.spline_ref().refine(u)?;
+        Ok(Self::new(t, c).with_extrapolation(self.extrapolate))
+    }
+
+    /// Simplifies the curve by removing interior knots, as long as the curve moves less than `tolerance`
+    /// 
+    /// Knots are removed one at a time, each time removing the knot giving the smallest change, following Tiller's
+    /// knot removal algorithm. The change of the curve is bounded by the largest distance between the original
+    /// control points, and those of the simplified curve with the knot inserted again, and the sum of these
+    /// bounds is returned together with the simplified curve. Knots at the ends of the domain, and knots
+    /// with multiplicity `K+1`, are never removed. The extrapolation method of the curve is kept, except for
+    /// [Extrapolate::Periodic], which is replaced by [Extrapolate::Clamp], as the ends of a periodic curve can
+    /// change differently, and no longer match.
+    pub fn remove_knots(&self, tolerance: f64) -> Result<(Self, f64)> {
+        let (t, c, bound) = self.spline_ref().remove_knots(tolerance)?;
+        Ok((Self::new(t, c).with_extrapolation(self.extrapolate.sub_curve()), bound))
+    }
+
+    /// Part of the curve for parameter values from `a` to `b`, as a new curve with a clamped knot vector
+    /// 
+    /// The knots `a` and `b` are inserted until they have a multiplicity of `K+1`, and the knots outside `a..=b` are
+    /// removed, such that the new curve is exactly equal to the original curve on its domain `a..=b`. Both values
+    /// should be within the domain of the curve, with `a<b`. The extrapolation method of the curve is kept, except
+    /// for [Extrapolate::Periodic], which is replaced by [Extrapolate::Clamp].
+    pub fn trim(&self, a: f64, b: f64) -> Result<Self> {
+        let (t, c) = self.spline_ref().trim(a, b)?;
+        Ok(Self::new(t, c).with_extrapolation(self.extrapolate.sub_curve()))
+    }
+
+    /// Splits the curve at the parameter value `u`, into two curves with domains `t[K]..=u` and `u..=t[t.len()-K-1]`
+    /// 
+    /// See [trim][SplineCurve::trim]; the parameter value should be an interior point of the domain of the curve.
+    pub fn split_at(&self, u: f64) -> Result<(Self, Self)> {
+        let (tb, te) = self.domain();
+        Ok((self.trim(tb, u)?, self.trim(u, te)?))
+    }
+
+    /// Concatenates spline curves end-to-end into a single curve
+    /// 
+    /// The curves are clamped, and are joined at knots with multiplicity `K`, with the domain of each curve
+    /// shifted to start at the end of the previous curve, unless reparametrization is disabled in the options. The
+    /// end and start points of consecutive curves are moved to their midpoint, and for [Continuity::C1][crate::Continuity::C1]
+    /// and [Continuity::G1][crate::Continuity::G1] also the control points next to the joints, see [JoinOptions];
+    /// knots are inserted in curves with too few control points for this, such as Bézier curves of degree one or two,
+    /// without changing their shape. Gaps larger than the tolerance are reported as [SplineError::Gap] errors, and
+    /// an empty slice of curves as a [SplineError::NoCurves] error. The extrapolation method of the first curve is
+    /// used, except for [Extrapolate::Periodic].
+    pub fn concatenate(curves: &[Self], options: JoinOptions) -> Result<Self> {
+        let refs: Vec<SplineRef<'_>> = curves.iter().map(|s| s.spline_ref()).collect();
+        let (t, c) = crate::join::concatenate(&refs, options)?;
+        Ok(Self::new(t, c).with_extrapolation(curves[0].extrapolate.sub_curve()))
+    }
+
+    /// The same curve, traversed in the opposite direction, on the same domain
+    /// 
+    /// The knots are mirrored in the domain, with a knot `t` mapped to `t[K]+t[t.len()-K-1]-t`, and the order of the
+    /// control points is reversed, such that the new curve at `u` is the original curve at `t[K]+t[t.len()-K-1]-u`.
+    /// First derivatives, and all other odd order derivatives, change sign. The domain is kept exactly, but other
+    /// knots can differ from the original knots by rounding errors when a curve is reversed twice.
+    pub fn reversed(&self) -> Self {
+        let (t, c) = self.spline_ref().reversed();
+        Self::new(t, c).with_extrapolation(self.extrapolate)
+    }
+
+    /// The same curve, with its domain mapped linearly onto `a..=b`
+    /// 
+    /// Only the knots change, and the control points are kept; for example, use `s.reparametrize(0.0, 1.0)` to
+    /// normalize the parameter range of a curve. Derivatives of order `m` are scaled by the `m`-th power of the ratio
+    /// of the old and new domain lengths. Requires finite values, with `a<b`.
+    pub fn reparametrize(&self, a: f64, b: f64) -> Result<Self> {
+        let t = self.spline_ref().reparametrize(a, b)?;
+        Ok(Self::new(t, self.c.clone()).with_extrapolation(self.extrapolate))
+    }
+
+    /// Applies the affine transform `matrix * x + translation` to the curve
+    /// 
+    /// As B-spline curves are affine invariant, transforming the control points transforms all the points of the
+    /// curve, and the matrix is applied to all derivatives too. The matrix is given as an array of rows, for
+    /// example `[[a, b], [c, d]]` maps a point `(x, y)` to `(a x + b y, c x + d y)`.
+    pub fn transform(&self, matrix: [[f64; N]; N], translation: [f64; N]) -> Self {
+        let c = self.spline_ref().transform(matrix.concat().as_slice(), &translation);
+        Self::new(self.t.clone(), c).with_extrapolation(self.extrapolate)
+    }
+
+    /// Translates the curve by the vector `v`
+    pub fn translate(&self, v: [f64; N]) -> Self {
+        self.transform(diagonal([1.0; N]), v)
+    }
+
+    /// Scales the curve by the factor `f`, relative to the origin
+    pub fn scale(&self, f: f64) -> Self {
+        self.scale_axes([f; N])
+    }
+
+    /// Scales each coordinate of the curve by its own factor, relative to the origin
+    pub fn scale_axes(&self, f: [f64; N]) -> Self {
+        self.transform(diagonal(f), [0.0; N])
+    }
+
+    /// Represents the curve exactly as a spline curve of a higher degree `K1`
+    /// 
+    /// The degree of the resulting curve is a type parameter, for example `let s5: SplineCurve<5,2> = s.elevate_degree()?;`,
+    /// and should be at least `K`. The new curve has a clamped knot vector, with the multiplicities of its interior
+    /// knots increased by `K1-K`, such that it has the same continuity as the original curve. The control points are
+    /// found segment by segment, so the work grows linearly with the number of knots.
+    pub fn elevate_degree<const K1: usize>(&self) -> Result<SplineCurve<K1, N>> {
+        if K1 < K {
+            return Err(SplineError::DegreeMismatch { expected: K, got: K1 });
         }
-        if nc!=n-(K+1) {
-            return Err(format!("Expected {} coefficient values, got {}", N*(n+K+1), N*nc).into());
+        let (t, c) = self.spline_ref().elevate_degree(K1)?;
+        Ok(SplineCurve::new(t, c).with_extrapolation(self.extrapolate))
+    }
+
+    /// Approximates the curve by a spline curve of a lower degree `K1`, with a deviation of at most `tolerance`
+    /// 
+    /// The multiplicities of the interior knots are reduced by `K-K1`, but are at least one, and the coefficients
+    /// are found by a least squares fit. The largest deviation between the curves, sampled in each knot interval, is
+    /// returned with the new curve, or a [SplineError::ToleranceExceeded] error if it is larger than `tolerance`;
+    /// if so, [refine][SplineCurve::refine] the curve first to reduce the error. As for
+    /// [remove_knots][SplineCurve::remove_knots], [Extrapolate::Periodic] is replaced by [Extrapolate::Clamp].
+    pub fn reduce_degree<const K1: usize>(&self, tolerance: f64) -> Result<(SplineCurve<K1, N>, f64)> {
+        if K == 0 {
+            return Err(SplineError::ZeroDegree);
+        } else if K1 >= K {
+            return Err(SplineError::DegreeMismatch { expected: K - 1, got: K1 });
+        }
+        let (t, c, error) = self.spline_ref().reduce_degree(K1)?;
+        if error > tolerance {
+            return Err(SplineError::ToleranceExceeded { tolerance, error });
         }
-        let mut v: Vec<f64> = Vec::with_capacity(u.len() * N); // x,y,..x,y coordinates
+        Ok((SplineCurve::new(t, c).with_extrapolation(self.extrapolate.sub_curve()), error))
+    }
 
-        let mut i = self.k;
-        let mut u_prev = f64::NEG_INFINITY;
-        let mut d = [0.0; DE_BOOR_SIZE]; // want to use K+1 here, but currently not allowed yet by the compiler
+    /// Length of the curve, for parameter values from `a` to `b`
+    /// 
+    /// The length is calculated by adaptive Gauss-Legendre quadrature of the length of the first derivative, for each
+    /// knot interval, with a relative tolerance of about `1E-12`. As for [integrate][SplineCurve::integrate], only the
+    /// part of the range within the domain of the curve is taken into account, and if `b<a` the length is negative.
+    /// Bounds which are NaN or infinite result in a [NonFiniteInput][SplineError::NonFiniteInput] error.
+    pub fn arc_length(&self, a: f64, b: f64) -> Result<f64> {
+        crate::arclength::arc_length(&self.spline_ref(), a, b)
+    }
 
-        for &t in u {
-            if t <= u_prev {
-                return Err("x values should be sorted in strict increasing order".into());
-            } else {
-                u_prev = t;
-            };
+    /// Parameter value at which the length of the curve, from the start of its domain, is `s`
+    /// 
+    /// This is the inverse of [arc_length][SplineCurve::arc_length], with arc lengths outside the range of the curve
+    /// clamped to the ends of its domain; an error is returned for arc lengths which are NaN or infinite. Use an
+    /// [arc_length_evaluator][SplineCurve::arc_length_evaluator] when evaluating the curve for many arc lengths.
+    pub fn parameter_at_arc_length(&self, s: f64) -> Result<f64> {
+        let table = crate::arclength::ArcLengthTable::new(&self.spline_ref())?;
+        table.parameter(&self.spline_ref(), s)
+    }
 
-            // clamp x to interval tb..=te
-            let arg = if t < self.t[self.k] || t > self.t[n - self.k - 1] {
-                t.clamp(self.t[self.k], self.t[n - K - 1])
-            } else {
-                t
-            };
+    /// Evaluator using arc length as parameter, to move along the curve at constant speed, see [ArcLengthEvaluator]
+    pub fn arc_length_evaluator(&self) -> Result<ArcLengthEvaluator<'_, K, N>> {
+        ArcLengthEvaluator::new(self)
+    }
+
+    /// Derivative of the spline curve, as a new spline curve of degree `K-1`
+    /// 
+    /// As the degree of a spline curve is a type parameter, the degree of the resulting curve has to be specified too,
+    /// for example `let ds: SplineCurve<2,1> = s.derivative()?;` for a cubic spline, and an error is returned if
+    /// this is not equal to `K-1`. The derivative curve has the same dimension, and its knots are the knots of the
+    /// original curve, with the first and the last knot removed.
+    pub fn derivative<const K1: usize>(&self) -> Result<SplineCurve<K1, N>> {
+        if K == 0 {
+            return Err(SplineError::ZeroDegree);
+        } else if K1 != K - 1 {
+            return Err(SplineError::DegreeMismatch { expected: K - 1, got: K1 });
+        }
+        let (t, c) = self.spline_ref().derivative()?;
+        Ok(SplineCurve::new(t, c).with_extrapolation(self.extrapolate))
+    }
+
+    /// Antiderivative, or indefinite integral, of the spline curve, as a new spline curve of degree `K+1`
+    /// 
+    /// Similar to [derivative][SplineCurve::derivative], the degree of the resulting curve has to be specified, and
+    /// has to be equal to `K+1`. The knot vector is extended with one knot at either end, and the integration
+    /// constant is chosen such that its first coefficient is zero; for a clamped knot vector this means that the
+    /// antiderivative is zero at the start of the curve. The antiderivative of a [periodic][SplineCurve::periodic]
+    /// curve is only periodic if the integrals over a period are zero.
+    pub fn antiderivative<const K2: usize>(&self) -> Result<SplineCurve<K2, N>> {
+        if K2 != K + 1 {
+            return Err(SplineError::DegreeMismatch { expected: K + 1, got: K2 });
+        }
+        let (t, c) = self.spline_ref().antiderivative()?;
+        Ok(SplineCurve::new(t, c).with_extrapolation(self.extrapolate))
+    }
 
-            // find knot interval which contains x=arg
-            while !(arg >= self.t[i] && arg <= self.t[i + 1]) {
-                i += 1
+    /// Definite integrals of the N coordinate functions of the curve, for parameter values from `a` to `b`
+    /// 
+    /// The integrals are exact, and are calculated using the antiderivative of the curve, and the integration range
+    /// can start or end anywhere within a knot interval. Only the part of the range within the domain of the
+    /// curve, `t[k]..=t[n-k-1]`, is taken into account. If `b<a`, the integrals change sign.
+    pub fn integrate(&self, a: f64, b: f64) -> Result<[f64; N]> {
+        let mut v = [0.0; N];
+        v.copy_from_slice(&self.spline_ref().integrate(a, b)?);
+        Ok(v)
+    }
+
+}
+
+impl<const K: usize> SplineCurve<K, 2> {
+    /// Rotates a two-dimensional curve counter-clockwise by `angle` radians, around the origin
+    pub fn rotate(&self, angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        self.transform([[cos, -sin], [sin, cos]], [0.0; 2])
+    }
+}
+
+impl<const K: usize> SplineCurve<K, 3> {
+    /// Rotates a three-dimensional curve by `angle` radians around `axis`, through the origin
+    /// 
+    /// The rotation is counter-clockwise when looking from the tip of the axis towards the origin; the axis does not
+    /// have to be normalized, and an [InvalidAxis][SplineError::InvalidAxis] error is returned if it is zero or not
+    /// finite.
+    pub fn rotate(&self, axis: [f64; 3], angle: f64) -> Result<Self> {
+        let l = axis.iter().map(|a| a * a).sum::<f64>().sqrt();
+        if l == 0.0 || !l.is_finite() {
+            return Err(SplineError::InvalidAxis);
+        }
+        let [x, y, z] = [axis[0] / l, axis[1] / l, axis[2] / l];
+        let (sin, cos) = angle.sin_cos();
+        let c1 = 1.0 - cos;
+        Ok(self.transform(
+            [
+                [cos + x * x * c1, x * y * c1 - z * sin, x * z * c1 + y * sin],
+                [y * x * c1 + z * sin, cos + y * y * c1, y * z * c1 - x * sin],
+                [z * x * c1 - y * sin, z * y * c1 + x * sin, cos + z * z * c1],
+            ],
+            [0.0; 3],
+        ))
+    }
+}
+
+/// Diagonal matrix, with the values `d` on its diagonal.
+fn diagonal<const N: usize>(d: [f64; N]) -> [[f64; N]; N] {
+    let mut matrix = [[0.0; N]; N];
+    for (i, row) in matrix.iter_mut().enumerate() {
+        row[i] = d[i];
+    }
+    matrix
+}
+
+/// Extrapolation methods, for parameter values outside the domain `t[k]..=t[n-k-1]` of a spline curve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Extrapolate {
+    /// Clamps parameter values to the domain, such that values and derivatives are those at the ends of the domain.
+    #[default]
+    Clamp,
+    /// Returns an error for parameter values outside the domain.
+    Error,
+    /// Returns NaN values, for values and derivatives.
+    NaN,
+    /// Holds the curve at the values at the ends of the domain, with all derivatives zero.
+    Constant,
+    /// Continues the curve along a straight line, using the first derivative at the ends of the domain.
+    Linear,
+    /// Continues the polynomials of the first and last knot intervals.
+    Polynomial,
+    /// Wraps parameter values around, using the length of the domain as period.
+    Periodic,
+}
+
+impl Extrapolate {
+    pub(crate) fn is_clamp(&self) -> bool {
+        *self == Extrapolate::Clamp
+    }
+
+    /// Extrapolation method for a part of a curve, which is not periodic.
+    pub(crate) fn sub_curve(self) -> Self {
+        if self == Extrapolate::Periodic { Extrapolate::Clamp } else { self }
+    }
+}
+
+/// Side from which a spline curve is evaluated at a knot
+/// 
+/// At knots with multiplicity `m`, a spline curve of degree `k` has only `k-m` continuous derivatives, and for
+/// `m=k+1` the curve itself is discontinuous. By default, the limits from the right are used, except at the end of
+/// the domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Side {
+    /// Limit from the left, using the knot interval ending at the knot; at the start of the domain the first
+    /// interval is used.
+    Left,
+    /// Limit from the right, using the knot interval starting at the knot; at the end of the domain the last
+    /// interval is used.
+    #[default]
+    Right,
+}
+
+/// Borrowed spline curve data, used for the algorithms shared by [SplineCurve] and [DynSplineCurve].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SplineRef<'a> {
+    pub t: &'a [f64],
+    pub c: &'a [f64],
+    pub k: usize,
+    pub n: usize,
+    pub extrapolate: Extrapolate,
+    pub side: Side,
+}
+
+impl<'a> SplineRef<'a> {
+    pub fn with_side(mut self, side: Side) -> Self {
+        self.side = side;
+        self
+    }
+
+    /// Lists all invariants violated by the spline curve, see [SplineCurve::validate].
+    pub fn validate(&self) -> Vec<SplineError> {
+        let (t, c, k, n) = (self.t, self.c, self.k, self.n);
+        let mut errors = Vec::new();
+        if n == 0 {
+            errors.push(SplineError::ZeroDimension);
+        }
+        if t.len() < 2 * (k + 1) {
+            errors.push(SplineError::TooFewKnots { required: 2 * (k + 1), got: t.len() });
+        } else if n > 0 && c.len() != n * (t.len() - k - 1) {
+            errors.push(SplineError::CoefficientCountMismatch { expected: n * (t.len() - k - 1), got: c.len() });
+        }
+        errors.extend(t.iter().enumerate().filter(|(_, x)| !x.is_finite()).map(|(index, _)| SplineError::NonFiniteKnot { index }));
+        errors.extend(c.iter().enumerate().filter(|(_, x)| !x.is_finite()).map(|(index, _)| SplineError::NonFiniteCoefficient { index }));
+        errors.extend(t.windows(2).enumerate().filter(|(_, w)| w[0] > w[1]).map(|(index, _)| SplineError::NonMonotoneKnots { index }));
+
+        let mut index = 0;
+        while index < t.len() {
+            let multiplicity = t[index..].iter().take_while(|&&x| x == t[index]).count();
+            if multiplicity > k + 1 {
+                errors.push(SplineError::KnotMultiplicity { index, multiplicity, max: k + 1 });
             }
+            index += multiplicity.max(1);
+        }
+        if t.len() >= 2 * (k + 1) && t[k] >= t[t.len() - k - 1] {
+            errors.push(SplineError::EmptyDomain);
+        }
+        errors
+    }
 
-            // calculate spline values
-            for dim in 0..N {
-                // copy relevant c values into d
-                for (j, dm) in d.iter_mut().enumerate().take(K + 1) {
-                    *dm = self.c[dim * nc + j + i - self.k];
-                }
+    /// Checks the dimension, and the number of knots and coefficients, as required for evaluation.
+    pub fn check_coefficients(&self) -> Result<()> {
+        let nt = self.t.len();
+        if self.n == 0 {
+            return Err(SplineError::ZeroDimension);
+        } else if nt < 2 * (self.k + 1) {
+            return Err(SplineError::TooFewKnots { required: 2 * (self.k + 1), got: nt });
+        }
+        let nc = self.c.len() / self.n;
+        if nc<(self.k+1) {
+            return Err(SplineError::TooFewCoefficients { required: self.k + 1, got: nc });
+        }
+        if self.c.len() != self.n * (nt - (self.k + 1)) {
+            return Err(SplineError::CoefficientCountMismatch { expected: self.n * (nt - (self.k + 1)), got: self.c.len() });
+        }
+        Ok(())
+    }
+
+    /// Spline values, and/or derivatives of order `order_start` to `order_end`, in the layout as described in
+    /// [SplineCurve::evaluate_with_derivatives].
+    pub fn evaluate(&self, u: &[f64], order_start: usize, order_end: usize) -> Result<Vec<f64>> {
+        self.check_coefficients()?;
+        let m = self.n * (order_end - order_start + 1);
+        let mut v: Vec<f64> = vec![0.0; u.len() * m]; // x,y,..x,y coordinates
 
-                v.push(self.deboor(i, arg, &mut d))
+        let mut i = self.k;
+        let mut d = vec![0.0; self.k + 1];
+        for (&x, vx) in u.iter().zip(v.chunks_mut(m)) {
+            if let Err(distance) = self.evaluate_point(x, order_start, order_end, &mut i, &mut d, vx) {
+                return Err(SplineError::OutOfDomain { u: x, distance });
             }
         }
         Ok(v)
     }
 
-
-    /// Calulates spline coordinates for a collection of parameter values
+    /// Spline values, and/or derivatives of order `order_start` to `order_end`, for a single parameter value `x`
     /// 
-    /// The coordinates are given as a one-dimensional array, starting with the N ---with N the dimension of the cuve---  coordinates of the firt point,
-    /// followed by the coordinates of all the other points. For example, for a two-dimensional curve (N=2), the (x,y)-coordinates are given as
-    /// [x0, y0, x1, y1, x2, ...] and for a three-dimensional curve, with coordinates (x,y,z) you will get [x0, y0, z0, x1, y1, z1, x2, y2 ...].
-    /// If you need to convert them into individual coordinate arrays, I suggest to use the [transpose][crate::transpose] function.
-    pub fn eval(&mut self, t: f64) -> std::result::Result<f64, f64> {
+    /// The knot interval `i` is used as starting point for the search of the knot interval containing `x`, and is
+    /// updated, and `d` is a work array with `k+1` elements. The results are stored in `v`. An error, containing
+    /// the distance to the domain, is only returned when using [Extrapolate::Error].
+    pub fn evaluate_point(&self, x: f64, order_start: usize, order_end: usize, i: &mut usize, d: &mut [f64], v: &mut [f64]) -> std::result::Result<(), f64> {
+        let (t, c, k, n) = (self.t, self.c, self.k, self.n);
+        let nt = t.len();
+        let nc = c.len() / n;
+        let (tb, te) = (t[k], t[nt - k - 1]);
 
-        let n = self.t.len();
-        let nc = self.c.len() / N;
+        let arg = if x < tb || x > te {
+            match self.extrapolate {
+                Extrapolate::Clamp | Extrapolate::Constant | Extrapolate::Linear => x.clamp(tb, te),
+                Extrapolate::Error => return Err(if x < tb { x - tb } else { x - te }),
+                Extrapolate::NaN => {
+                    v.fill(f64::NAN);
+                    return Ok(());
+                }
+                Extrapolate::Polynomial => x,
+                Extrapolate::Periodic => tb + (x - tb).rem_euclid(te - tb),
+            }
+        } else {
+            x
+        };
 
-        if t < self.t[self.k] {
-            Err(t - self.t[self.k])
-        } else if t > self.t[n - self.k - 1] {
-            Err(t - self.t[n - self.k - 1])
+        // find knot interval which contains x=arg: for increasing parameter values continue where we left-off,
+        // and otherwise use a binary search; at, and beyond, the ends of the domain the first and last non-empty
+        // intervals are used, which for unclamped knot vectors might not be the first and last intervals
+        let forward = match self.side {
+            Side::Right => arg >= t[*i],
+            Side::Left => arg > t[*i],
+        };
+        if arg >= te {
+            *i = find_interval(t, k, te, Side::Left);
+        } else if arg <= tb {
+            *i = find_interval(t, k, tb, Side::Right);
+        } else if !forward {
+            *i = find_interval(t, k, arg, self.side);
         } else {
-            // find knot interval which contains x=arg
-            let mut i = if let Some(i_prev) = self.i  {  
-                if t>self.t[i_prev] { // continue where we left-off
-                   // println!("reusing");
-                    i_prev
+            // skip intervals, including empty intervals for repeated knots
+            while !match self.side {
+                Side::Right => arg < t[*i + 1],
+                Side::Left => arg <= t[*i + 1],
+            } {
+                *i += 1
+            }
+        }
+
+        // calculate spline values, and derivatives if requested
+        let mut value = |order: usize, dim: usize| {
+            // copy relevant c values into d
+            d.copy_from_slice(&c[dim * nc + *i - k..=dim * nc + *i]);
+            deboor_derivative(t, k, *i, arg, order, d)
+        };
+        for (order, vo) in (order_start..=order_end).zip(v.chunks_mut(n)) {
+            for (dim, vd) in vo.iter_mut().enumerate() {
+                *vd = if x == arg || matches!(self.extrapolate, Extrapolate::Clamp | Extrapolate::Polynomial | Extrapolate::Periodic) {
+                    value(order, dim)
+                } else if self.extrapolate == Extrapolate::Constant {
+                    if order == 0 { value(0, dim) } else { 0.0 }
                 } else {
-                  //  println!("restarting");
-                    self.k
+                    // linear
+                    match order {
+                        0 => value(0, dim) + (x - arg) * value(1, dim),
+                        1 => value(1, dim),
+                        _ => 0.0,
+                    }
+                };
+            }
+        }
+        Ok(())
+    }
+
+    /// Domain `t[k]..=t[nt-k-1]` of the spline curve, with `nt` the number of knots.
+    pub fn domain(&self) -> (f64, f64) {
+        (self.t[self.k], self.t[self.t.len() - self.k - 1])
+    }
+
+    /// Checks if the first and last `k+1` knots are equal to the start and end of the domain.
+    pub fn is_clamped(&self) -> bool {
+        let (t, k) = (self.t, self.k);
+        let (tb, te) = self.domain();
+        t[..=k].iter().all(|&x| x == tb) && t[t.len() - k - 1..].iter().all(|&x| x == te)
+    }
+
+    /// Knots and coefficients after inserting the knot `x`, `multiplicity` times, see [SplineCurve::insert_knot].
+    pub fn insert_knot(&self, x: f64, multiplicity: usize) -> Result<(Vec<f64>, Vec<f64>)> {
+        let current = self.t.iter().filter(|&&ti| ti == x).count();
+        if current + multiplicity > self.k + 1 {
+            let index = self.t.partition_point(|&ti| ti < x);
+            return Err(SplineError::KnotMultiplicity { index, multiplicity: current + multiplicity, max: self.k + 1 });
+        }
+        let (mut t, mut c) = (self.t.to_vec(), self.c.to_vec());
+        for _ in 0..multiplicity {
+            (t, c) = SplineRef { t: &t, c: &c, ..*self }.boehm(x)?;
+        }
+        Ok((t, c))
+    }
+
+    /// Knots and coefficients after inserting the knot `x` once, using Boehm's algorithm
+    /// 
+    /// The curve is unchanged, but has one more coefficient, for each dimension. At the end of the domain, the
+    /// last non-empty interval is used, and the knot has to be within the domain of the curve.
+    fn boehm(&self, x: f64) -> Result<(Vec<f64>, Vec<f64>)> {
+        self.check_coefficients()?;
+        let (t, c, k, n) = (self.t, self.c, self.k, self.n);
+        let nc = c.len() / n;
+        let (tb, te) = self.domain();
+        if !(tb..=te).contains(&x) {
+            return Err(SplineError::OutOfDomain { u: x, distance: if x < tb { x - tb } else { x - te } });
+        }
+        let i = find_interval(t, k, x, if x == te { Side::Left } else { Side::Right });
+
+        let mut ti = Vec::with_capacity(t.len() + 1);
+        ti.extend_from_slice(&t[..=i]);
+        ti.push(x);
+        ti.extend_from_slice(&t[i + 1..]);
+
+        let mut ci = Vec::with_capacity(n * (nc + 1));
+        for dim in 0..n {
+            let c = &c[dim * nc..(dim + 1) * nc];
+            ci.extend_from_slice(&c[..=i - k]);
+            for j in i + 1 - k..=i {
+                let alpha = (x - t[j]) / (t[j + k] - t[j]);
+                ci.push((1.0 - alpha) * c[j - 1] + alpha * c[j]);
+            }
+            ci.extend_from_slice(&c[i..]);
+        }
+        Ok((ti, ci))
+    }
+
+    /// Knots and coefficients after inserting all knots in `x`, using the Oslo algorithm, see [SplineCurve::refine].
+    pub fn refine(&self, x: &[f64]) -> Result<(Vec<f64>, Vec<f64>)> {
+        self.check_coefficients()?;
+        let (t, c, k, n) = (self.t, self.c, self.k, self.n);
+        let nc = c.len() / n;
+        let (tb, te) = self.domain();
+        if let Some(&u) = x.iter().find(|u| !(tb..=te).contains(*u)) {
+            return Err(SplineError::OutOfDomain { u, distance: if u < tb { u - tb } else { u - te } });
+        }
+
+        // merged knot vector
+        let mut tau = Vec::with_capacity(t.len() + x.len());
+        tau.extend_from_slice(t);
+        tau.extend_from_slice(x);
+        tau.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut index = 0;
+        while index < tau.len() {
+            let multiplicity = tau[index..].iter().take_while(|&&v| v == tau[index]).count();
+            if multiplicity > k + 1 {
+                return Err(SplineError::KnotMultiplicity { index, multiplicity, max: k + 1 });
+            }
+            index += multiplicity;
+        }
+
+        // each new coefficient is the blossom of the curve, at the k interior knots of its basis function, using
+        // the polynomial piece of a non-empty knot interval within the support of the basis function
+        let ntau = tau.len();
+        let mut d = vec![0.0; k + 1];
+        let mut ci = vec![0.0; n * (ntau - k - 1)];
+        let nci = ntau - k - 1;
+        for j in 0..nci {
+            let xm = (j.max(k)..=(j + k).min(ntau - k - 2))
+                .find(|&l| tau[l] < tau[l + 1])
+                .map_or(tau[j + k].clamp(tb, te), |l| 0.5 * (tau[l] + tau[l + 1]));
+            let mu = find_interval(t, k, xm, Side::Right);
+            for dim in 0..n {
+                d.copy_from_slice(&c[dim * nc + mu - k..=dim * nc + mu]);
+                for r in 1..=k {
+                    for jj in (r..=k).rev() {
+                        let i = mu + jj - k;
+                        let alpha = (tau[j + r] - t[i]) / (t[i + k + 1 - r] - t[i]);
+                        d[jj] = (1.0 - alpha) * d[jj - 1] + alpha * d[jj];
+                    }
                 }
-            } else { // new start or restart 
-                self.k
-            };
+                ci[dim * nci + j] = d[k];
+            }
+        }
+        Ok((tau, ci))
+    }
+
+    /// Knots, coefficients, and error bound, after removing interior knots, see [SplineCurve::remove_knots].
+    pub fn remove_knots(&self, tolerance: f64) -> Result<(Vec<f64>, Vec<f64>, f64)> {
+        self.check_coefficients()?;
+        let (k, n) = (self.k, self.n);
+        let (tb, te) = self.domain();
+        let (mut t, mut c) = (self.t.to_vec(), self.c.to_vec());
+        let mut bound = 0.0;
+        loop {
+            // candidate with the smallest error, of all interior knots with multiplicity of at most k
+            let s = SplineRef { t: &t, c: &c, ..*self };
+            let mut best: Option<(Vec<f64>, Vec<f64>, f64)> = None;
+            let mut r = t.partition_point(|&ti| ti <= tb);
+            while r < t.len() && t[r] < te {
+                let multiplicity = t[r..].iter().take_while(|&&ti| ti == t[r]).count();
+                r += multiplicity;
+                if multiplicity <= k {
+                    let (tr, cr) = s.remove_knot(r - 1, multiplicity);
+                    // error bound: largest distance between the original control points, and those of the reduced
+                    // curve with the knot inserted again, which have the same basis functions
+                    let (_, ci) = SplineRef { t: &tr, c: &cr, ..*self }.boehm(t[r - 1])?;
+                    let nc = c.len() / n;
+                    let error = (0..nc)
+                        .map(|j| (0..n).map(|dim| (c[dim * nc + j] - ci[dim * nc + j]).powi(2)).sum::<f64>().sqrt())
+                        .fold(0.0, f64::max);
+                    if best.as_ref().map_or(true, |b| error < b.2) {
+                        best = Some((tr, cr, error));
+                    }
+                }
+            }
+            match best {
+                Some((tr, cr, error)) if bound + error <= tolerance => {
+                    t = tr;
+                    c = cr;
+                    bound += error;
+                }
+                _ => break,
+            }
+        }
+        Ok((t, c, bound))
+    }
 
-            let mut d = [0.0; DE_BOOR_SIZE]; // want to use K+1 here, but currently not allowed yet by the compiler
-            // TODO: test with 10?
+    /// Knots and coefficients after removing the interior knot `t[r]`, with multiplicity `s`, with `r` the last
+    /// index of the knot value
+    /// 
+    /// This inverts knot insertion: of the `k-s+1` coefficients changed by inserting the knot into the reduced
+    /// curve, the first half of the new coefficients is solved from the left, and the second half from the right,
+    /// as in Tiller's knot removal algorithm. The result is exact if the knot was inserted before, and otherwise
+    /// the curve changes.
+    fn remove_knot(&self, r: usize, s: usize) -> (Vec<f64>, Vec<f64>) {
+        let (t, c, k, n) = (self.t, self.c, self.k, self.n);
+        let nc = c.len() / n;
+        let u = t[r];
+        let mut tr = t.to_vec();
+        tr.remove(r);
 
-            while !(t >= self.t[i] && t <= self.t[i + 1]) {
-                i += 1
+        let (first, last) = (r - k, r - s);
+        let m = last - first; // number of new coefficients
+        let alpha = |j: usize| (u - tr[j]) / (tr[j + k] - tr[j]);
+        let mut cr = Vec::with_capacity(n * (nc - 1));
+        let mut q = vec![0.0; m + 2]; // new coefficients first-1..=last
+        for dim in 0..n {
+            let p = &c[dim * nc..(dim + 1) * nc];
+            q[0] = p[first - 1];
+            q[m + 1] = p[last + 1];
+            let h = m.div_ceil(2);
+            for j in first..first + h {
+                let a = alpha(j);
+                q[j - first + 1] = (p[j] - (1.0 - a) * q[j - first]) / a;
             }
-            self.i = Some(i); // store for next evaluation
+            for j in (first + h + 1..=last).rev() {
+                let a = alpha(j);
+                q[j - first] = (p[j] - a * q[j - first + 1]) / (1.0 - a);
+            }
+            cr.extend_from_slice(&p[..first]);
+            cr.extend_from_slice(&q[1..=m]);
+            cr.extend_from_slice(&p[last + 1..]);
+        }
+        (tr, cr)
+    }
+
+    /// Knots and coefficients of the curve with the higher degree `kn`, see [SplineCurve::elevate_degree]
+    /// 
+    /// The curve is split into Bézier segments, by inserting the interior knots until they have a multiplicity of
+    /// at least `k`, and each segment is elevated on its own. The interior knots, now with a multiplicity of at
+    /// least `kn`, are then removed again, down to their original multiplicity plus `kn-k`. All steps only change
+    /// a few control points at a time, and are exact, apart from rounding errors.
+    pub fn elevate_degree(&self, kn: usize) -> Result<(Vec<f64>, Vec<f64>)> {
+        let (k, n) = (self.k, self.n);
+        let (t, c) = self.clamped()?;
+        if kn == k {
+            return Ok((t, c));
+        }
+        let e = kn - k;
+
+        // Bézier segments, joined at knots with multiplicity k, or with multiplicity k+1 at discontinuities
+        let knots = multiplicities(&t);
+        let inserted: Vec<f64> = knots.iter().flat_map(|&(x, m)| std::iter::repeat(x).take(k.saturating_sub(m))).collect();
+        let (td, cd) = SplineRef { t: &t, c: &c, extrapolate: Extrapolate::Clamp, ..*self }.refine(&inserted)?;
+        let ncd = cd.len() / n;
+
+        // weights of the control points of a Bézier segment of degree k, for the control points of degree kn
+        let w: Vec<f64> = (0..=kn)
+            .flat_map(|j| (0..=k).map(move |i| if i <= j && j - i <= e { binomial(k, i) * binomial(e, j - i) / binomial(kn, j) } else { 0.0 }))
+            .collect();
+        let mut tn = Vec::with_capacity(td.len() * (kn + 1) / (k + 1) + 2 * e);
+        for (x, m) in multiplicities(&td) {
+            tn.extend(std::iter::repeat(x).take(m + e));
+        }
+        let mut cn = Vec::with_capacity(n * (tn.len() - kn - 1));
+        for dim in 0..n {
+            let p = &cd[dim * ncd..(dim + 1) * ncd];
+            for i in (k..ncd).filter(|&i| td[i] < td[i + 1]) {
+                // segments joined at a knot with multiplicity k share their first and last control points
+                let first = if td[i - k] == td[i] { 0 } else { 1 };
+                cn.extend((first..=kn).map(|j| (0..=k).map(|r| w[j * (k + 1) + r] * p[i - k + r]).sum::<f64>()));
+            }
+        }
+
+        // remove the knots inserted for the decomposition
+        let (tb, te) = (t[0], t[t.len() - 1]);
+        for &(x, m) in knots.iter().filter(|&&(x, _)| x > tb && x < te) {
+            for s in (m + e + 1..=m.max(k) + e).rev() {
+                let r = tn.partition_point(|&v| v <= x) - 1;
+                (tn, cn) = SplineRef { t: &tn, c: &cn, k: kn, ..*self }.remove_knot(r, s);
+            }
+        }
+        Ok((tn, cn))
+    }
+
+    /// Knots and coefficients of the curve with the lower degree `kn`, and the largest deviation found, see
+    /// [SplineCurve::reduce_degree]
+    /// 
+    /// The knot vector is clamped, and the multiplicities of the interior knots are reduced by `k-kn`, but are at
+    /// least one. The coefficients are found by a least squares fit, with `k+1` points in each knot interval.
+    pub fn reduce_degree(&self, kn: usize) -> Result<(Vec<f64>, Vec<f64>, f64)> {
+        let k = self.k;
+        let (t, c) = self.clamped()?;
+        let s = SplineRef { t: &t, c: &c, extrapolate: Extrapolate::Clamp, ..*self };
+        let (tb, te) = s.domain();
 
-            // calculate spline values 
-            for dim in 0..N {
-                // copy relevant c values into d
-                for (j, dm) in d.iter_mut().enumerate().take(K + 1) {
-                    *dm = self.c[dim * nc + j + i - self.k];
+        let mut tn = Vec::with_capacity(t.len());
+        for (x, m) in multiplicities(&t) {
+            let mn = if x == tb || x == te { kn + 1 } else { (m + kn).saturating_sub(k).max(1) };
+            tn.extend(std::iter::repeat(x).take(mn));
+        }
+
+        let mut sites = vec![(tb, Side::Right), (te, Side::Left)];
+        for w in tn.windows(2).filter(|w| w[0] < w[1]) {
+            sites.extend((0..=k).map(|j| (w[0] + (w[1] - w[0]) * (j as f64 + 0.5) / (k + 1) as f64, Side::Right)));
+        }
+        let cn = s.fit(&tn, kn, &sites)?;
+
+        // largest deviation, sampled in all knot intervals, including their ends
+        let mut error: f64 = 0.0;
+        let r = SplineRef { t: &tn, c: &cn, k: kn, ..s };
+        let m = 4 * (k + 1);
+        for w in tn.windows(2).filter(|w| w[0] < w[1]) {
+            for j in 0..=m {
+                let (x, side) = (w[0] + (w[1] - w[0]) * j as f64 / m as f64, if j == m { Side::Left } else { Side::Right });
+                let v = s.with_side(side).evaluate(&[x], 0, 0)?;
+                let vn = r.with_side(side).evaluate(&[x], 0, 0)?;
+                error = error.max(v.iter().zip(vn.iter()).map(|(a, b)| (a - b).powi(2)).sum::<f64>().sqrt());
+            }
+        }
+        Ok((tn, cn, error))
+    }
+
+    /// Coefficients of a spline curve with knots `tn` and degree `kn`, fitted to this curve at the given parameter
+    /// values, by interpolation if the number of parameter values equals the number of coefficients, and otherwise
+    /// by a least squares fit.
+    fn fit(&self, tn: &[f64], kn: usize, sites: &[(f64, Side)]) -> Result<Vec<f64>> {
+        let n = self.n;
+        let nc = tn.len() - kn - 1;
+        let m = sites.len();
+        let mut a = vec![0.0; m * nc];
+        let mut y = vec![0.0; m * n];
+        let mut b = vec![0.0; kn + 1];
+        for (row, &(x, side)) in sites.iter().enumerate() {
+            let i = find_interval(tn, kn, x, side);
+            basis_functions(tn, kn, i, x, &mut b);
+            a[row * nc + i - kn..=row * nc + i].copy_from_slice(&b);
+            y[row * n..(row + 1) * n].copy_from_slice(&self.with_side(side).evaluate(&[x], 0, 0)?);
+        }
+        if m != nc {
+            // normal equations
+            let mut ata = vec![0.0; nc * nc];
+            let mut aty = vec![0.0; nc * n];
+            for row in 0..m {
+                let ar = &a[row * nc..(row + 1) * nc];
+                for (i, &ai) in ar.iter().enumerate().filter(|(_, &ai)| ai != 0.0) {
+                    for (j, &aj) in ar.iter().enumerate() {
+                        ata[i * nc + j] += ai * aj;
+                    }
+                    for dim in 0..n {
+                        aty[i * n + dim] += ai * y[row * n + dim];
+                    }
                 }
             }
-            Ok(self.deboor(i, t, &mut d))
+            a = ata;
+            y = aty;
+        }
+        solve(&mut a, &mut y, nc, n)?;
+        Ok((0..n).flat_map(|dim| y.iter().skip(dim).step_by(n).cloned().collect::<Vec<f64>>()).collect())
+    }
+
+    /// Knots and coefficients of the clamped form of a spline curve, see [SplineCurve::clamped].
+    pub fn clamped(&self) -> Result<(Vec<f64>, Vec<f64>)> {
+        let (tb, te) = self.domain();
+        self.trim(tb, te)
+    }
+
+    /// Knots and coefficients of the part of a spline curve between `a` and `b`, see [SplineCurve::trim].
+    pub fn trim(&self, a: f64, b: f64) -> Result<(Vec<f64>, Vec<f64>)> {
+        self.check_coefficients()?;
+        let (k, n) = (self.k, self.n);
+        let (tb, te) = self.domain();
+        if let Some(&u) = [a, b].iter().find(|u| !(tb..=te).contains(*u)) {
+            return Err(SplineError::OutOfDomain { u, distance: if u < tb { u - tb } else { u - te } });
+        }
+        if a >= b {
+            return Err(SplineError::EmptyDomain);
+        }
+
+        // increase the multiplicities of the knots at the new ends to k+1
+        let (mut t, mut c) = (self.t.to_vec(), self.c.to_vec());
+        for x in [a, b] {
+            let multiplicity = t.iter().filter(|&&ti| ti == x).count();
+            (t, c) = SplineRef { t: &t, c: &c, ..*self }.insert_knot(x, k + 1 - multiplicity)?;
+        }
+
+        // remove the knots outside the new domain, and the coefficients of their basis functions, which are zero
+        // within the new domain
+        let nc = c.len() / n;
+        let start = t.partition_point(|&ti| ti < a);
+        let end = t.partition_point(|&ti| ti <= b);
+        let cc = (0..n).flat_map(|dim| c[dim * nc + start..dim * nc + end - k - 1].iter().cloned()).collect();
+        Ok((t[start..end].to_vec(), cc))
+    }
+
+    /// Knots and coefficients of the reversed curve, see [SplineCurve::reversed].
+    pub fn reversed(&self) -> (Vec<f64>, Vec<f64>) {
+        let (tb, te) = self.domain();
+        let nc = self.c.len() / self.n;
+        // the domain ends are swapped exactly, as their sum is rounded
+        let t = self.t.iter().rev().map(|&x| if x == tb { te } else if x == te { tb } else { tb + te - x }).collect();
+        let c = self.c.chunks(nc).flat_map(|c| c.iter().rev().cloned()).collect();
+        (t, c)
+    }
+
+    /// Knots of the curve with its domain mapped linearly onto `a..=b`, see [SplineCurve::reparametrize].
+    pub fn reparametrize(&self, a: f64, b: f64) -> Result<Vec<f64>> {
+        let (tb, te) = self.domain();
+        if a >= b || !a.is_finite() || !b.is_finite() {
+            return Err(SplineError::EmptyDomain);
+        }
+        let scale = (b - a) / (te - tb);
+        Ok(self.t.iter().map(|x| if *x == te { b } else { a + (x - tb) * scale }).collect())
+    }
+
+    /// Coefficients after applying the affine transform `matrix * x + translation` to all control points, with the
+    /// `n` by `n` matrix given row by row, see [SplineCurve::transform].
+    pub fn transform(&self, matrix: &[f64], translation: &[f64]) -> Vec<f64> {
+        let n = self.n;
+        let nc = self.c.len() / n;
+        let mut c = vec![0.0; self.c.len()];
+        for (i, ci) in c.chunks_mut(nc).enumerate() {
+            let row = &matrix[i * n..(i + 1) * n];
+            for (j, cij) in ci.iter_mut().enumerate() {
+                *cij = translation[i] + row.iter().enumerate().map(|(dim, m)| m * self.c[dim * nc + j]).sum::<f64>();
+            }
+        }
+        c
+    }
+
+    /// Knots and coefficients of the derivative of a spline curve, see [SplineCurve::derivative].
+    pub fn derivative(&self) -> Result<(Vec<f64>, Vec<f64>)> {
+        let (t, c, k, n) = (self.t, self.c, self.k, self.n);
+        let nt = t.len();
+        let nc = c.len() / n;
+        if k == 0 {
+            return Err(SplineError::ZeroDegree);
+        }
+        if c.len() != n * (nt - (k + 1)) {
+            return Err(SplineError::CoefficientCountMismatch { expected: n * (nt - (k + 1)), got: c.len() });
+        }
+
+        let td = t[1..nt - 1].to_vec();
+        let mut cd = Vec::with_capacity(n * (nc - 1));
+        for dim in 0..n {
+            let c = &c[dim * nc..(dim + 1) * nc];
+            for j in 1..nc {
+                let dt = t[j + k] - t[j];
+                cd.push(if dt > 0.0 { k as f64 * (c[j] - c[j - 1]) / dt } else { 0.0 });
+            }
+        }
+        Ok((td, cd))
+    }
+
+    /// Knots and coefficients of the antiderivative of a spline curve, see [SplineCurve::antiderivative].
+    pub fn antiderivative(&self) -> Result<(Vec<f64>, Vec<f64>)> {
+        let (t, c, k, n) = (self.t, self.c, self.k, self.n);
+        let nt = t.len();
+        let nc = c.len() / n;
+        if c.len() != n * (nt - (k + 1)) {
+            return Err(SplineError::CoefficientCountMismatch { expected: n * (nt - (k + 1)), got: c.len() });
+        }
+
+        let mut ti = Vec::with_capacity(nt + 2);
+        ti.push(t[0]);
+        ti.extend_from_slice(t);
+        ti.push(t[nt - 1]);
+
+        let mut ci = Vec::with_capacity(n * (nc + 1));
+        for dim in 0..n {
+            let mut sum = 0.0;
+            ci.push(sum);
+            for (j, cj) in c[dim * nc..(dim + 1) * nc].iter().enumerate() {
+                sum += cj * (t[j + k + 1] - t[j]) / (k + 1) as f64;
+                ci.push(sum);
+            }
+        }
+        Ok((ti, ci))
+    }
+
+    /// Definite integrals of the coordinate functions of a spline curve, see [SplineCurve::integrate].
+    pub fn integrate(&self, a: f64, b: f64) -> Result<Vec<f64>> {
+        let n = self.n;
+        let (tb, te) = (self.t[self.k], self.t[self.t.len() - self.k - 1]);
+        let (t, c) = self.antiderivative()?;
+        let nc = c.len() / n;
+        let k = self.k + 1;
+        let mut d = vec![0.0; k + 1];
+        let mut v = vec![0.0; n];
+        for (x, sign) in [(b, 1.0), (a, -1.0)] {
+            let x = x.clamp(tb, te);
+            let i = find_interval(&t, k, x, Side::Right);
+            for (dim, vd) in v.iter_mut().enumerate() {
+                d.copy_from_slice(&c[dim * nc + i - k..=dim * nc + i]);
+                *vd += sign * deboor(&t, k, i, x, &mut d);
+            }
         }
+        Ok(v)
+    }
+}
+
+/// Knots and coefficients of a periodic spline curve, of degree `k` and dimension `n`, from the knots and coefficients
+/// of one period, see [SplineCurve::periodic].
+pub(crate) fn periodic_extension(t: &[f64], c: &[f64], k: usize, n: usize) -> Result<(Vec<f64>, Vec<f64>)> {
+    if n == 0 {
+        return Err(SplineError::ZeroDimension);
+    }
+    let m = t.len().saturating_sub(1);
+    if m < k.max(1) {
+        return Err(SplineError::TooFewKnots { required: k.max(1) + 1, got: t.len() });
+    }
+    if c.len() != n * m {
+        return Err(SplineError::CoefficientCountMismatch { expected: n * m, got: c.len() });
+    }
+    let period = t[m] - t[0];
+    if period.is_nan() || period <= 0.0 {
+        return Err(SplineError::EmptyDomain);
     }
 
-    pub(crate) fn deboor(&self, i: usize, x: f64, d: &mut [f64; DE_BOOR_SIZE]) -> f64 {
+    let mut tp = Vec::with_capacity(m + 1 + 2 * k);
+    tp.extend(t[m - k..m].iter().map(|x| x - period));
+    tp.extend_from_slice(t);
+    tp.extend(t[1..=k].iter().map(|x| x + period));
+
+    let mut cp = Vec::with_capacity(n * (m + k));
+    for dim in 0..n {
+        let c = &c[dim * m..(dim + 1) * m];
+        cp.extend_from_slice(c);
+        cp.extend_from_slice(&c[..k]);
+    }
+    Ok((tp, cp))
+}
+
+/// Values of the `k+1` B-spline basis functions of degree `k`, which are non-zero in knot interval `i`, at `x`,
+/// using the Cox-de Boor recursion
+/// 
+/// The values of the basis functions `i-k..=i` are stored in `b`, which should have `k+1` elements.
+pub(crate) fn basis_functions(t: &[f64], k: usize, i: usize, x: f64, b: &mut [f64]) {
+    b[0] = 1.0;
+    for j in 1..=k {
+        let mut saved = 0.0;
+        for r in 0..j {
+            let right = t[i + r + 1] - x;
+            let left = x - t[i + 1 + r - j];
+            let temp = b[r] / (right + left);
+            b[r] = saved + right * temp;
+            saved = left * temp;
+        }
+        b[j] = saved;
+    }
+}
 
-        for r in 1..self.k + 1 {
-            for j in (r..=self.k).into_iter().rev() {
-                let alpha =
-                    (x - self.t[j + i - self.k]) / (self.t[j + 1 + i - r] - self.t[j + i - self.k]);
-                d[j] = (1.0 - alpha) * d[j - 1] + alpha * d[j]
+/// Solves the linear equations `a x = b`, with `a` a square matrix of size `m`, and `b` containing `nrhs` right hand
+/// sides, both stored row by row, using Gaussian elimination with partial pivoting
+/// 
+/// The solutions replace the right hand sides in `b`, and `a` is overwritten.
+pub(crate) fn solve(a: &mut [f64], b: &mut [f64], m: usize, nrhs: usize) -> Result<()> {
+    for col in 0..m {
+        let p = (col..m).max_by(|&i, &j| a[i * m + col].abs().total_cmp(&a[j * m + col].abs())).unwrap();
+        let pivot = a[p * m + col];
+        if pivot == 0.0 || !pivot.is_finite() {
+            return Err(SplineError::SingularSystem);
+        }
+        if p != col {
+            for j in 0..m {
+                a.swap(p * m + j, col * m + j);
+            }
+            for j in 0..nrhs {
+                b.swap(p * nrhs + j, col * nrhs + j);
+            }
+        }
+        for row in col + 1..m {
+            let f = a[row * m + col] / pivot;
+            if f != 0.0 {
+                for j in col..m {
+                    a[row * m + j] -= f * a[col * m + j];
+                }
+                for j in 0..nrhs {
+                    b[row * nrhs + j] -= f * b[col * nrhs + j];
+                }
             }
         }
-        d[self.k]
     }
+    for col in (0..m).rev() {
+        for j in 0..nrhs {
+            let sum: f64 = (col + 1..m).map(|i| a[col * m + i] * b[i * nrhs + j]).sum();
+            b[col * nrhs + j] = (b[col * nrhs + j] - sum) / a[col * m + col];
+        }
+    }
+    Ok(())
+}
+
+/// Distinct knot values, in order, with their multiplicities
+fn multiplicities(t: &[f64]) -> Vec<(f64, usize)> {
+    let mut knots: Vec<(f64, usize)> = Vec::new();
+    for &x in t {
+        match knots.last_mut() {
+            Some((v, m)) if *v == x => *m += 1,
+            _ => knots.push((x, 1)),
+        }
+    }
+    knots
+}
 
-    // https://stackoverflow.com/questions/57507696/b-spline-derivative-using-de-boors-algorithm
-   // pub(crate) fn deboor_derivative(&self, i: usize, x: f64, d: &mut [f64; 6]) -> (f64, f64) {
-   //     todo!()
-   // }
+/// Binomial coefficient `n` over `k`, with `k<=n`
+fn binomial(n: usize, k: usize) -> f64 {
+    (0..k).fold(1.0, |b, i| b * (n - i) as f64 / (i + 1) as f64)
+}
 
+/// Knot interval `i`, with `t[i] <= x < t[i+1]`, or with `t[i] < x <= t[i+1]` when using [Side::Left], for a spline
+/// of degree `k`, using a binary search
+/// 
+/// The interval is limited to the domain of the spline, `k..=t.len()-k-2`, which also gives the first and last
+/// interval for the start and end of the domain.
+pub(crate) fn find_interval(t: &[f64], k: usize, x: f64, side: Side) -> usize {
+    match side {
+        Side::Right => t.partition_point(|&ti| ti <= x),
+        Side::Left => t.partition_point(|&ti| ti < x),
+    }.clamp(k + 1, t.len() - k - 1) - 1
+}
+
+/// De Boor's algorithm, for a spline of degree `k` with knots `t`, at `x` in knot interval `i`
+/// 
+/// The `k+1` coefficients relevant for the interval `i` are expected in the first `k+1` elements of `d`,
+/// which is used as a work array.
+pub(crate) fn deboor(t: &[f64], k: usize, i: usize, x: f64, d: &mut [f64]) -> f64 {
+    for r in 1..k + 1 {
+        for j in (r..=k).rev() {
+            let alpha =
+                (x - t[j + i - k]) / (t[j + 1 + i - r] - t[j + i - k]);
+            d[j] = (1.0 - alpha) * d[j - 1] + alpha * d[j]
+        }
+    }
+    d[k]
+}
+
+/// Derivative of order `order` at `x`, in knot interval `i`, using the coefficients copied into `d`.
+/// 
+/// The coefficients are first differenced `order` times, in place, which gives the local coefficients of the
+/// derivative spline, of degree `k-order`, and these are then used in a regular de Boor evaluation.
+/// See <https://stackoverflow.com/questions/57507696/b-spline-derivative-using-de-boors-algorithm>.
+pub(crate) fn deboor_derivative(t: &[f64], k: usize, i: usize, x: f64, order: usize, d: &mut [f64]) -> f64 {
+    if order == 0 {
+        return deboor(t, k, i, x, d);
+    } else if order > k {
+        return 0.0;
+    }
+
+    // coefficients of the derivative spline
+    for r in 1..=order {
+        for j in (r..=k).rev() {
+            let dt = t[j + 1 + i - r] - t[j + i - k];
+            d[j] = if dt > 0.0 {
+                (k + 1 - r) as f64 * (d[j] - d[j - 1]) / dt
+            } else {
+                0.0
+            };
+        }
+    }
+
+    // de Boor for the reduced degree spline, using d[order..=k]
+    for r in 1..=(k - order) {
+        for j in (order + r..=k).rev() {
+            let alpha =
+                (x - t[j + i - k]) / (t[j + 1 + i - r - order] - t[j + i - k]);
+            d[j] = (1.0 - alpha) * d[j - 1] + alpha * d[j]
+        }
+    }
+    d[k]
 }
 
 /// Creates coordinate vectors for a vector of coordinates
@@ -183,7 +1378,7 @@ impl<const K: usize, const N: usize> SplineCurve<K, N> {
 /// vec![vec![x0, x1, x2, ...], vec![y0, y1, y2, ..], vec![z0, z1, z2, ...]]
 pub fn transpose(xyn: &[f64], n: usize) -> Vec<Vec<f64>>{
     let m = xyn.len()/n; 
-    let mut vn: Vec<Vec<f64>> = std::iter::repeat(Vec::with_capacity(m)).take(n).collect();
+    let mut vn: Vec<Vec<f64>> = std::iter::repeat_with(|| Vec::with_capacity(m)).take(n).collect();
     for v in xyn.chunks(n) {
         for (i,x) in v.iter().enumerate() {
             vn[i].push(*x);
@@ -194,15 +1389,24 @@ pub fn transpose(xyn: &[f64], n: usize) -> Vec<Vec<f64>>{
 
 #[cfg(test)]
 mod tests {
-    use super::SplineCurve;
+    use super::{Extrapolate, Side, SplineCurve, SplineError};
     use approx::assert_abs_diff_eq;
 
+    /// Clamped cubic curve, with two interior knots, and parameter values covering its domain `0..=4`.
+    fn cubic_2d() -> (SplineCurve<3, 2>, Vec<f64>) {
+        let s = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.5, 4.0, 4.0, 4.0, 4.0],
+            vec![0.0, 1.0, 3.0, 4.0, 3.5, 5.0, 0.0, 2.0, -1.0, 1.0, 3.0, 0.5],
+        );
+        (s, (0..=40).map(|i| i as f64 * 0.1).collect())
+    }
+
     // spline test values from https://docs.rs/bspline/1.0.0/bspline/index.html crate
 
     #[test]
     fn linear_bspline() {
         let x = vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
-        let y = vec![0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
+        let y = [0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
 
         let s: SplineCurve<1, 1> = SplineCurve::new(vec![0.0, 0.0, 1.0, 1.0], vec![0.0, 1.0]);
         let yt = s.evaluate(&x).unwrap();
@@ -242,7 +1446,7 @@ mod tests {
     fn cubic_bspline() {
         // expected
         let x = vec![-2.0, -1.5, -1.0, -0.6, 0.0, 0.5, 1.5, 2.0];
-        let y = vec![0.0, 0.125, 1.0, 2.488, 4.0, 2.875, 0.12500001, 0.0];
+        let y = [0.0, 0.125, 1.0, 2.488, 4.0, 2.875, 0.12500001, 0.0];
 
         let s: SplineCurve<3, 1> = SplineCurve::new(
             vec![-2.0, -2.0, -2.0, -2.0, -1.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
@@ -263,15 +1467,16 @@ mod tests {
     fn cubic_bspline_single_values() {
         // expected
         let x = vec![-2.0, -1.5, -1.0, -0.6, 0.0, 0.5, 1.5, 2.0];
-        let y = vec![0.0, 0.125, 1.0, 2.488, 4.0, 2.875, 0.12500001, 0.0];
+        let y = [0.0, 0.125, 1.0, 2.488, 4.0, 2.875, 0.12500001, 0.0];
 
-        let mut s: SplineCurve<3, 1> = SplineCurve::new(
+        let s: SplineCurve<3, 1> = SplineCurve::new(
             vec![-2.0, -2.0, -2.0, -2.0, -1.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
             vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0, 0.0],
         );
 
         //
-        let yt: Vec<f64> = x.into_iter().map(|x|s.eval(x).unwrap()).collect();
+        let mut e = s.evaluator().unwrap();
+        let yt: Vec<f64> = x.into_iter().map(|x|e.eval(x).unwrap()[0]).collect();
         y.iter()
             .zip(yt.iter())
             .for_each(|(&a, &b)| assert_abs_diff_eq!(a, b, epsilon = 1E-7));
@@ -316,4 +1521,734 @@ mod tests {
             .for_each(|(&a, &b)| assert_abs_diff_eq!(a, b, epsilon = 1E-7));
         s.plot("test.png", (2000,1000)).unwrap();
     }
+
+    #[test]
+    fn cubic_bspline_derivatives() {
+        let x = [-2.0, -1.5, -1.0, -0.6, 0.0, 0.5, 1.5, 2.0];
+        let dy = [0.0, 0.75, 3.0, 3.96, 0.0, -3.75, -0.75, 0.0];
+        let ddy = [0.0, 3.0, 6.0, -1.2, -12.0, -3.0, 3.0, 0.0];
+
+        let s: SplineCurve<3, 1> = SplineCurve::new(
+            vec![-2.0, -2.0, -2.0, -2.0, -1.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+            vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0, 0.0],
+        );
+
+        let dyt = s.evaluate_derivative(&x, 1).unwrap();
+        dy.iter()
+            .zip(dyt.iter())
+            .for_each(|(&a, &b)| assert_abs_diff_eq!(a, b, epsilon = 1E-10));
+
+        let ddyt = s.evaluate_derivative(&x, 2).unwrap();
+        ddy.iter()
+            .zip(ddyt.iter())
+            .for_each(|(&a, &b)| assert_abs_diff_eq!(a, b, epsilon = 1E-10));
+
+        let dddyt = s.evaluate_derivative(&[-1.5, -0.5, 0.5, 1.5], 3).unwrap();
+        [6.0, -18.0, 18.0, -6.0].iter()
+            .zip(dddyt.iter())
+            .for_each(|(&a, &b)| assert_abs_diff_eq!(a, b, epsilon = 1E-10));
+
+        let d4yt = s.evaluate_derivative(&x, 4).unwrap();
+        d4yt.iter().for_each(|&b| assert_abs_diff_eq!(b, 0.0));
+    }
+
+    #[test]
+    fn cubic_bspline_2d_with_derivatives() {
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 0.3, 0.5, 1.0, 1.0, 1.0, 1.0],
+            vec![0.0, 0.5, 1.0, 3.0, 2.5, 1.0, 2.0, -3.0, 3.0, -3.0, 0.0, 1.0],
+        );
+        let u = [0.0, 0.1, 0.3, 0.45, 0.7, 1.0];
+        let v = s.evaluate_with_derivatives(&u, 2).unwrap();
+        let xy = s.evaluate(&u).unwrap();
+        let dxy = s.evaluate_derivative(&u, 1).unwrap();
+        let ddxy = s.evaluate_derivative(&u, 2).unwrap();
+        for (j, p) in v.chunks(6).enumerate() {
+            assert_abs_diff_eq!(p[0..2], xy[2*j..2*j+2], epsilon = 1E-12);
+            assert_abs_diff_eq!(p[2..4], dxy[2*j..2*j+2], epsilon = 1E-12);
+            assert_abs_diff_eq!(p[4..6], ddxy[2*j..2*j+2], epsilon = 1E-12);
+        }
+
+        // compare with central differences
+        let h = 1E-6;
+        for &t in &u[1..u.len()-1] {
+            let xy_m = s.evaluate(&[t - h]).unwrap();
+            let xy_p = s.evaluate(&[t + h]).unwrap();
+            let dxy = s.evaluate_derivative(&[t], 1).unwrap();
+            for dim in 0..2 {
+                assert_abs_diff_eq!(dxy[dim], (xy_p[dim] - xy_m[dim]) / (2.0 * h), epsilon = 1E-5);
+            }
+        }
+    }
+
+    #[test]
+    fn cubic_bspline_derivative_curve() {
+        let x = [-2.0, -1.5, -1.0, -0.6, 0.0, 0.5, 1.5, 2.0];
+        let s: SplineCurve<3, 1> = SplineCurve::new(
+            vec![-2.0, -2.0, -2.0, -2.0, -1.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+            vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0, 0.0],
+        );
+        let ds: SplineCurve<2, 1> = s.derivative().unwrap();
+        let dyt = ds.evaluate(&x).unwrap();
+        let dy = s.evaluate_derivative(&x, 1).unwrap();
+        dy.iter()
+            .zip(dyt.iter())
+            .for_each(|(&a, &b)| assert_abs_diff_eq!(a, b, epsilon = 1E-10));
+
+        let dds: SplineCurve<1, 1> = ds.derivative().unwrap();
+        let ddyt = dds.evaluate(&x).unwrap();
+        let ddy = s.evaluate_derivative(&x, 2).unwrap();
+        ddy.iter()
+            .zip(ddyt.iter())
+            .for_each(|(&a, &b)| assert_abs_diff_eq!(a, b, epsilon = 1E-10));
+
+        assert!(s.derivative::<3>().is_err());
+    }
+
+    #[test]
+    fn cubic_bspline_antiderivative_curve() {
+        let x = [-2.0, -1.5, -1.0, -0.6, 0.0, 0.5, 1.5, 2.0];
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![-2.0, -2.0, -2.0, -2.0, -1.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+            vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0, 0.0, 1.0, 2.0, 0.5, -1.0, 3.0, 1.0, 2.0],
+        );
+        let is: SplineCurve<4, 2> = s.antiderivative().unwrap();
+
+        // starts at zero, and its derivative is the original curve
+        assert_abs_diff_eq!(is.evaluate(&[-2.0]).unwrap()[..], [0.0, 0.0][..], epsilon = 1E-12);
+        let y = s.evaluate(&x).unwrap();
+        let dyt = is.evaluate_derivative(&x, 1).unwrap();
+        y.iter()
+            .zip(dyt.iter())
+            .for_each(|(&a, &b)| assert_abs_diff_eq!(a, b, epsilon = 1E-10));
+
+        // the cubic b-spline has unit area, times its coefficient 6
+        assert_abs_diff_eq!(is.evaluate(&[2.0]).unwrap()[0], 6.0, epsilon = 1E-12);
+
+        let ds: SplineCurve<3, 2> = is.derivative().unwrap();
+        assert_abs_diff_eq!(ds.evaluate(&x).unwrap()[..], y[..], epsilon = 1E-10);
+    }
+
+    #[test]
+    fn cubic_bspline_integrate() {
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![-2.0, -2.0, -2.0, -2.0, -1.0, 0.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+            vec![0.0, 0.0, 0.0, 6.0, 0.0, 0.0, 0.0, 1.0, 2.0, 0.5, -1.0, 3.0, 1.0, 2.0],
+        );
+        assert_abs_diff_eq!(s.integrate(-2.0, 2.0).unwrap()[0], 6.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.integrate(-2.0, -1.5).unwrap()[0], 0.015625, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.integrate(0.0, 0.5).unwrap()[0], 1.796875, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.integrate(0.5, 0.0).unwrap()[0], -1.796875, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.integrate(-5.0, 5.0).unwrap()[0], 6.0, epsilon = 1E-12);
+
+        // compare with Simpson's rule, within a knot interval, and over several intervals
+        for &(a, b) in &[(-1.7, -1.2), (-0.3, 1.9), (-2.0, 2.0)] {
+            let m = 2000;
+            let h = (b - a) / m as f64;
+            let u: Vec<f64> = (0..=m).map(|j| a + j as f64 * h).collect();
+            let xy = s.evaluate(&u).unwrap();
+            let iv = s.integrate(a, b).unwrap();
+            for dim in 0..2 {
+                let simpson: f64 = xy.chunks(2).enumerate()
+                    .map(|(j, p)| if j == 0 || j == m { p[dim] } else if j % 2 == 1 { 4.0 * p[dim] } else { 2.0 * p[dim] })
+                    .sum::<f64>() * h / 3.0;
+                assert_abs_diff_eq!(iv[dim], simpson, epsilon = 1E-9);
+            }
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 0.3, 0.5, 1.0, 1.0, 1.0, 1.0],
+            vec![0.0, 0.5, 1.0, 3.0, 2.5, 1.0, 2.0, -3.0, 3.0, -3.0, 0.0, 1.0],
+        );
+        let json = s.to_json().unwrap();
+        let v: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(v["k"], 3);
+        assert_eq!(v["n"], 2);
+        assert!(v.get("i").is_none());
+
+        let r = SplineCurve::<3, 2>::from_json(&json).unwrap();
+        assert_eq!(r.t, s.t);
+        assert_eq!(r.c, s.c);
+        let u = [0.0, 0.25, 0.5, 0.75, 1.0];
+        assert_abs_diff_eq!(r.evaluate(&u).unwrap()[..], s.evaluate(&u).unwrap()[..]);
+
+        // wrong degree, dimension, or number of coefficients
+        assert!(matches!(SplineCurve::<2, 2>::from_json(&json), Err(SplineError::DegreeMismatch { expected: 2, got: 3 })));
+        assert!(matches!(SplineCurve::<3, 1>::from_json(&json), Err(SplineError::DimensionMismatch { expected: 1, got: 2 })));
+        let e = SplineCurve::<3, 2>::from_json(r#"{"t":[0,0,0,0,1,1,1,1],"c":[0,1,2,3,0,1,2],"k":3,"n":2}"#).unwrap_err();
+        assert!(matches!(e, SplineError::Invalid(ref errors) if matches!(errors[..], [SplineError::CoefficientCountMismatch { expected: 8, got: 7 }])));
+        assert!(matches!(SplineCurve::<3, 2>::from_json("{"), Err(SplineError::Json(_))));
+    }
+
+    #[test]
+    fn high_degree_bspline() {
+        // for a single knot interval, the coefficients for x² are j(j-1)/(k(k-1))
+        let x = [0.0, 0.1, 0.25, 0.5, 0.9, 1.0];
+        let k = 9;
+        let t: Vec<f64> = std::iter::repeat(0.0).take(k + 1).chain(std::iter::repeat(1.0).take(k + 1)).collect();
+        let c: Vec<f64> = (0..=k).map(|j| (j * j.saturating_sub(1)) as f64 / (k * (k - 1)) as f64).collect();
+        let s = SplineCurve::<9, 1>::try_new(t, c).unwrap();
+        let y = s.evaluate(&x).unwrap();
+        x.iter()
+            .zip(y.iter())
+            .for_each(|(&a, &b)| assert_abs_diff_eq!(a * a, b, epsilon = 1E-12));
+        let dy = s.evaluate_derivative(&x, 1).unwrap();
+        x.iter()
+            .zip(dy.iter())
+            .for_each(|(&a, &b)| assert_abs_diff_eq!(2.0 * a, b, epsilon = 1E-12));
+
+        // septic spline with interior knots, derivative curve against pointwise derivatives
+        let t = vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.2, 0.5, 0.7, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+        let c = vec![0.0, 0.3, -0.2, 1.0, 0.5, 0.7, 2.0, -1.0, 0.4, 0.1, 0.0];
+        let s = SplineCurve::<7, 1>::try_new(t, c).unwrap();
+        let ds: SplineCurve<6, 1> = s.derivative().unwrap();
+        assert_abs_diff_eq!(ds.evaluate(&x).unwrap()[..], s.evaluate_derivative(&x, 1).unwrap()[..], epsilon = 1E-10);
+        let mut e = s.evaluator().unwrap();
+        let y: Vec<f64> = x.iter().map(|&x| e.eval(x).unwrap()[0]).collect();
+        assert_abs_diff_eq!(y[..], s.evaluate(&x).unwrap()[..], epsilon = 1E-12);
+
+        assert!(SplineCurve::<7, 1>::try_new(vec![0.0; 15], vec![0.0; 8]).is_err());
+    }
+
+    #[test]
+    fn cubic_bspline_3d_single_values() {
+        let s: SplineCurve<3, 3> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 0.3, 0.5, 1.0, 1.0, 1.0, 1.0],
+            vec![
+                0.0, 0.5, 1.0, 3.0, 2.5, 1.0,
+                2.0, -3.0, 3.0, -3.0, 0.0, 1.0,
+                1.0, 1.0, 2.0, 2.0, 3.0, 3.0,
+            ],
+        ).with_extrapolation(Extrapolate::Error);
+        let u = [0.0, 0.1, 0.3, 0.45, 0.7, 1.0];
+        let xyz = s.evaluate(&u).unwrap();
+
+        // in increasing order, reusing the knot interval, and in random order
+        let mut e = s.evaluator().unwrap();
+        for &j in &[0, 1, 2, 3, 4, 5, 3, 0, 5, 2] {
+            assert_abs_diff_eq!(e.eval(u[j]).unwrap()[..], xyz[3 * j..3 * j + 3], epsilon = 1E-12);
+        }
+        assert!(matches!(e.eval(-0.5), Err(SplineError::OutOfDomain { distance, .. }) if distance == -0.5));
+        assert!(matches!(e.eval(1.25), Err(SplineError::OutOfDomain { distance, .. }) if distance == 0.25));
+    }
+
+    #[test]
+    fn unsorted_parameter_values() {
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 0.3, 0.5, 1.0, 1.0, 1.0, 1.0],
+            vec![0.0, 0.5, 1.0, 3.0, 2.5, 1.0, 2.0, -3.0, 3.0, -3.0, 0.0, 1.0],
+        );
+        let u = [0.0, 0.1, 0.3, 0.45, 0.7, 1.0];
+        let xy = s.evaluate(&u).unwrap();
+        let dxy = s.evaluate_derivative(&u, 1).unwrap();
+
+        let j = [4, 1, 1, 5, 0, 3, 3, 2, 0];
+        let uj: Vec<f64> = j.iter().map(|&j| u[j]).collect();
+        let xyj = s.evaluate(&uj).unwrap();
+        let dxyj = s.evaluate_derivative(&uj, 1).unwrap();
+        for (m, &j) in j.iter().enumerate() {
+            assert_abs_diff_eq!(xyj[2 * m..2 * m + 2], xy[2 * j..2 * j + 2], epsilon = 1E-12);
+            assert_abs_diff_eq!(dxyj[2 * m..2 * m + 2], dxy[2 * j..2 * j + 2], epsilon = 1E-12);
+        }
+    }
+
+    #[test]
+    fn extrapolation() {
+        // f(t) = 3t + 3t² - 4t³
+        let s: SplineCurve<3, 1> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+            vec![0.0, 1.0, 3.0, 2.0],
+        );
+        let f = |t: f64| 3.0 * t + 3.0 * t * t - 4.0 * t * t * t;
+        let df = |t: f64| 3.0 + 6.0 * t - 12.0 * t * t;
+        let u = [-0.5, 0.25, 1.5];
+        let ev = |s: &SplineCurve<3, 1>, order| s.evaluate_derivative(&u, order).unwrap();
+
+        let s = s.with_extrapolation(Extrapolate::Clamp);
+        assert_abs_diff_eq!(ev(&s, 0)[..], [f(0.0), f(0.25), f(1.0)][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(ev(&s, 1)[..], [df(0.0), df(0.25), df(1.0)][..], epsilon = 1E-12);
+
+        let s = s.with_extrapolation(Extrapolate::Error);
+        assert!(s.evaluate(&u).is_err());
+        assert!(matches!(s.evaluate(&u), Err(SplineError::OutOfDomain { u, .. }) if u == -0.5));
+        assert!(matches!(s.evaluator().unwrap().eval(1.5), Err(SplineError::OutOfDomain { distance, .. }) if distance == 0.5));
+        assert_abs_diff_eq!(s.evaluate(&u[1..2]).unwrap()[0], f(0.25), epsilon = 1E-12);
+
+        let s = s.with_extrapolation(Extrapolate::NaN);
+        let v = ev(&s, 0);
+        assert!(v[0].is_nan() && !v[1].is_nan() && v[2].is_nan());
+
+        let s = s.with_extrapolation(Extrapolate::Constant);
+        assert_abs_diff_eq!(ev(&s, 0)[..], [f(0.0), f(0.25), f(1.0)][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(ev(&s, 1)[..], [0.0, df(0.25), 0.0][..], epsilon = 1E-12);
+
+        let s = s.with_extrapolation(Extrapolate::Linear);
+        assert_abs_diff_eq!(ev(&s, 0)[..], [f(0.0) - 0.5 * df(0.0), f(0.25), f(1.0) + 0.5 * df(1.0)][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(ev(&s, 1)[..], [df(0.0), df(0.25), df(1.0)][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(ev(&s, 2)[0], 0.0);
+
+        let s = s.with_extrapolation(Extrapolate::Polynomial);
+        assert_abs_diff_eq!(ev(&s, 0)[..], [f(-0.5), f(0.25), f(1.5)][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(ev(&s, 1)[..], [df(-0.5), df(0.25), df(1.5)][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluator().unwrap().eval(1.5).unwrap()[0], f(1.5), epsilon = 1E-12);
+
+        let s = s.with_extrapolation(Extrapolate::Periodic);
+        assert_abs_diff_eq!(ev(&s, 0)[..], [f(0.5), f(0.25), f(0.5)][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluator().unwrap().eval(-0.75).unwrap()[0], f(0.25), epsilon = 1E-12);
+
+        let json = s.to_json().unwrap();
+        assert!(json.contains(r#""extrapolate": "periodic""#));
+        assert_eq!(SplineCurve::<3, 1>::from_json(&json).unwrap().extrapolate, Extrapolate::Periodic);
+    }
+
+    #[test]
+    fn validation() {
+        let t = vec![0.0, 0.0, 0.0, 0.0, 0.3, 0.5, 1.0, 1.0, 1.0, 1.0];
+        let c = vec![0.0, 0.5, 1.0, 3.0, 2.5, 1.0];
+        assert!(SplineCurve::<3, 1>::try_new(t.clone(), c.clone()).is_ok());
+
+        // interior knot with multiplicity K+1 is allowed
+        let ti = vec![0.0, 0.0, 0.0, 0.0, 0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0, 1.0];
+        let ci = vec![0.0, 0.5, 1.0, 3.0, 2.5, 1.0, 0.0, 1.0];
+        assert!(SplineCurve::<3, 1>::try_new(ti, ci).is_ok());
+
+        let mut s = SplineCurve::<3, 1>::new(t, c);
+        s.t[4] = 0.7;
+        s.t[1] = f64::NAN;
+        s.c[2] = f64::INFINITY;
+        s.c.push(0.0);
+        s.k = 2;
+        match s.validate() {
+            Err(SplineError::Invalid(errors)) => {
+                assert_eq!(errors.len(), 5);
+                assert!(matches!(errors[0], SplineError::DegreeMismatch { expected: 3, got: 2 }));
+                assert!(matches!(errors[1], SplineError::CoefficientCountMismatch { expected: 6, got: 7 }));
+                assert!(matches!(errors[2], SplineError::NonFiniteKnot { index: 1 }));
+                assert!(matches!(errors[3], SplineError::NonFiniteCoefficient { index: 2 }));
+                assert!(matches!(errors[4], SplineError::NonMonotoneKnots { index: 4 }));
+            }
+            _ => panic!("expected validation errors"),
+        }
+
+        let e = SplineCurve::<1, 1>::try_new(vec![0.0, 0.0, 0.0, 1.0, 1.0], vec![0.0, 0.0, 1.0]).unwrap_err();
+        assert!(matches!(e, SplineError::Invalid(ref errors) if matches!(errors[..], [SplineError::KnotMultiplicity { index: 0, multiplicity: 3, max: 2 }])));
+        let e = SplineCurve::<1, 1>::try_new(vec![0.0, 1.0, 1.0, 2.0], vec![0.0, 0.0]).unwrap_err();
+        assert!(matches!(e, SplineError::Invalid(ref errors) if matches!(errors[..], [SplineError::EmptyDomain])));
+
+        // curves created without validation are checked before evaluation
+        let s = SplineCurve::<3, 1>::new(vec![0.0, 1.0], vec![0.0; 4]);
+        assert!(matches!(s.evaluate(&[0.5]), Err(SplineError::TooFewKnots { required: 8, got: 2 })));
+        assert!(matches!(s.evaluator(), Err(SplineError::TooFewKnots { .. })));
+    }
+
+    #[test]
+    fn repeated_interior_knots() {
+        // discontinuous linear spline, with a jump from 1 to 2 at u=1
+        let s: SplineCurve<1, 1> = SplineCurve::new(vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0], vec![0.0, 1.0, 2.0, 3.0]);
+        let u = [0.0, 0.5, 1.0, 1.5, 2.0];
+        assert_abs_diff_eq!(s.evaluate(&u).unwrap()[..], [0.0, 0.5, 2.0, 2.5, 3.0][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluate_limit(&u, 0, Side::Right).unwrap()[..], [0.0, 0.5, 2.0, 2.5, 3.0][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluate_limit(&u, 0, Side::Left).unwrap()[..], [0.0, 0.5, 1.0, 2.5, 3.0][..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluate_limit(&[1.0, 0.5, 1.0], 1, Side::Left).unwrap()[..], [1.0, 1.0, 1.0][..], epsilon = 1E-12);
+        let mut e = s.evaluator().unwrap().with_side(Side::Left);
+        assert_abs_diff_eq!(e.eval(1.0).unwrap()[0], 1.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(e.eval(2.0).unwrap()[0], 3.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(e.eval(0.0).unwrap()[0], 0.0, epsilon = 1E-12);
+
+        // cubic spline with a triple knot, continuous, but with a discontinuous first derivative
+        let s: SplineCurve<3, 1> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0],
+            vec![0.0, 1.0, 2.0, 3.0, 5.0, 7.0, 9.0],
+        );
+        let u = [0.5, 1.0, 1.0, 1.5];
+        let y = s.evaluate(&u).unwrap();
+        assert!(y.iter().all(|v| v.is_finite()));
+        assert_abs_diff_eq!(y[1], 3.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluate_limit(&u, 0, Side::Left).unwrap()[..], y[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluate_limit(&u[1..2], 1, Side::Left).unwrap()[0], 3.0, epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluate_limit(&u[1..2], 1, Side::Right).unwrap()[0], 6.0, epsilon = 1E-12);
+    }
+
+    #[test]
+    fn unclamped_knots() {
+        // open uniform quadratic and cubic spline curves
+        let s: SplineCurve<2, 2> = SplineCurve::new(
+            (0..9).map(|v| v as f64).collect(),
+            vec![0.0, 1.0, 3.0, 4.0, 3.0, 2.0, 0.0, 1.0, 0.0, 2.0, 1.0, 3.0],
+        );
+        assert_eq!(s.domain(), (2.0, 6.0));
+        assert!(!s.is_clamped());
+        let sc = s.clamped().unwrap();
+        assert!(sc.is_clamped());
+        assert_eq!(sc.domain(), (2.0, 6.0));
+        assert_eq!(sc.t, vec![2.0, 2.0, 2.0, 3.0, 4.0, 5.0, 6.0, 6.0, 6.0]);
+        let u: Vec<f64> = (0..=40).map(|i| 2.0 + i as f64 * 0.1).collect();
+        assert_abs_diff_eq!(s.evaluate(&u).unwrap()[..], sc.evaluate(&u).unwrap()[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.evaluate_derivative(&u, 1).unwrap()[..], sc.evaluate_derivative(&u, 1).unwrap()[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.integrate(2.0, 6.0).unwrap()[..], sc.integrate(2.0, 6.0).unwrap()[..], epsilon = 1E-12);
+        // start and end points of the uniform quadratic curve are midpoints of the first and last control legs
+        assert_abs_diff_eq!(s.evaluate(&[2.0, 6.0]).unwrap()[..], [0.5, 0.5, 2.5, 2.0][..], epsilon = 1E-12);
+
+        let s: SplineCurve<3, 1> = SplineCurve::new(vec![-3.0, -2.0, -1.0, 0.0, 0.5, 1.0, 1.0, 1.5, 2.0], vec![1.0, 3.0, 2.0, 4.0, 2.0]);
+        let sc = s.clamped().unwrap();
+        assert_eq!(sc.t, vec![0.0, 0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0]);
+        assert_abs_diff_eq!(s.evaluate(&[0.0, 0.3, 0.7, 1.0]).unwrap()[..], sc.evaluate(&[0.0, 0.3, 0.7, 1.0]).unwrap()[..], epsilon = 1E-12);
+        assert!(s.evaluate(&[1.0]).unwrap()[0].is_finite());
+    }
+
+    #[test]
+    fn periodic() {
+        // closed cubic curve through the corners of a square, with uniform knots
+        let s: SplineCurve<3, 2> = SplineCurve::periodic(
+            vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            vec![1.0, -1.0, -1.0, 1.0, 1.0, 1.0, -1.0, -1.0],
+        ).unwrap();
+        assert_eq!(s.domain(), (0.0, 4.0));
+        assert_eq!(s.t.len(), 11);
+        assert_eq!(s.extrapolate, Extrapolate::Periodic);
+
+        // no seam: values and derivatives match at the start and end of the domain, and wrap around
+        for order in 0..3 {
+            let v = s.evaluate_derivative(&[0.0, 4.0, 8.0, -4.0, 0.5, 4.5], order).unwrap();
+            assert_abs_diff_eq!(v[0..2], v[2..4], epsilon = 1E-12);
+            assert_abs_diff_eq!(v[0..2], v[4..6], epsilon = 1E-12);
+            assert_abs_diff_eq!(v[0..2], v[6..8], epsilon = 1E-12);
+            assert_abs_diff_eq!(v[8..10], v[10..12], epsilon = 1E-12);
+        }
+        // uniform cubic B-spline: points at the knots are (c[i] + 4 c[i+1] + c[i+2])/6
+        assert_abs_diff_eq!(s.evaluate(&[1.0]).unwrap()[..], [-2.0 / 3.0, -2.0 / 3.0][..], epsilon = 1E-12);
+
+        // derivatives are periodic too
+        let ds: SplineCurve<2, 2> = s.derivative().unwrap();
+        assert_abs_diff_eq!(ds.evaluate(&[0.0, 4.0, 2.5]).unwrap()[..4], s.evaluate_derivative(&[0.0, 4.0], 1).unwrap()[..], epsilon = 1E-12);
+
+        let json = s.to_json().unwrap();
+        assert!(json.contains("\"periodic\""));
+        let sj: SplineCurve<3, 2> = SplineCurve::from_json(&json).unwrap();
+        assert_abs_diff_eq!(sj.evaluate(&[-1.5, 7.25]).unwrap()[..], s.evaluate(&[2.5, 3.25]).unwrap()[..], epsilon = 1E-12);
+
+        assert!(matches!(SplineCurve::<3, 1>::periodic(vec![0.0, 1.0, 2.0], vec![1.0, 2.0]), Err(SplineError::TooFewKnots { .. })));
+        assert!(matches!(SplineCurve::<1, 1>::periodic(vec![0.0, 1.0, 2.0], vec![1.0]), Err(SplineError::CoefficientCountMismatch { .. })));
+    }
+
+    #[test]
+    fn knot_insertion() {
+        let (s, u) = cubic_2d();
+        let v = s.evaluate(&u).unwrap();
+
+        let s1 = s.insert_knot(2.0, 1).unwrap();
+        assert_eq!(s1.t, vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.0, 2.5, 4.0, 4.0, 4.0, 4.0]);
+        assert_eq!(s1.c.len(), 14);
+        assert_abs_diff_eq!(s1.evaluate(&u).unwrap()[..], v[..], epsilon = 1E-12);
+
+        // with multiplicity K, the curve passes through a control point
+        let s3 = s.insert_knot(2.0, 3).unwrap();
+        assert_abs_diff_eq!(s3.evaluate(&u).unwrap()[..], v[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s3.evaluate(&[2.0]).unwrap()[..], [s3.c[4], s3.c[4 + 9]][..], epsilon = 1E-12);
+        assert!(matches!(s3.insert_knot(2.0, 2), Err(SplineError::KnotMultiplicity { multiplicity: 5, max: 4, .. })));
+        assert!(matches!(s.insert_knot(5.0, 1), Err(SplineError::OutOfDomain { .. })));
+
+        // at an existing knot, up to multiplicity K+1, which splits the curve into independent pieces
+        let s4 = s.insert_knot(2.5, 3).unwrap();
+        assert_eq!(s4.t.iter().filter(|&&t| t == 2.5).count(), 4);
+        assert_abs_diff_eq!(s4.evaluate(&u).unwrap()[..], v[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s4.evaluate_limit(&[2.5], 1, Side::Left).unwrap()[..], s4.evaluate_limit(&[2.5], 1, Side::Right).unwrap()[..], epsilon = 1E-12);
+        assert!(matches!(s4.insert_knot(2.5, 1), Err(SplineError::KnotMultiplicity { multiplicity: 5, .. })));
+
+        // degree one: a control point is added on the control polygon
+        let p: SplineCurve<1, 2> = SplineCurve::new(vec![0.0, 0.0, 1.0, 2.0, 2.0], vec![0.0, 1.0, 3.0, 0.0, 2.0, 0.0]);
+        assert_eq!(p.insert_knot(1.5, 1).unwrap().c, vec![0.0, 1.0, 2.0, 3.0, 0.0, 2.0, 1.0, 0.0]);
+        assert_abs_diff_eq!(p.refine(&[0.25, 1.0]).unwrap().evaluate(&u[..=20]).unwrap()[..], p.evaluate(&u[..=20]).unwrap()[..], epsilon = 1E-12);
+
+        // Oslo refinement gives the same result as repeated insertion
+        let r = s.refine(&[3.0, 2.0, 0.5, 2.0, 2.0]).unwrap();
+        let b = s.insert_knot(0.5, 1).unwrap().insert_knot(2.0, 3).unwrap().insert_knot(3.0, 1).unwrap();
+        assert_eq!(r.t, b.t);
+        assert_abs_diff_eq!(r.c[..], b.c[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(r.evaluate(&u).unwrap()[..], v[..], epsilon = 1E-12);
+
+        // unclamped curve, with knots inserted at the ends of the domain
+        let s: SplineCurve<2, 1> = SplineCurve::new((0..8).map(|v| v as f64).collect(), vec![1.0, 3.0, 2.0, 4.0, 0.0]);
+        let r = s.refine(&[2.0, 3.5, 5.0, 5.0]).unwrap();
+        let u: Vec<f64> = (0..=30).map(|i| 2.0 + i as f64 * 0.1).collect();
+        assert_abs_diff_eq!(r.evaluate(&u).unwrap()[..], s.evaluate(&u).unwrap()[..], epsilon = 1E-12);
+    }
+
+    #[test]
+    fn knot_removal() {
+        let (s, u) = cubic_2d();
+        let v = s.evaluate(&u).unwrap();
+
+        // inserted knots are removed again, without error
+        let r = s.refine(&[0.5, 2.0, 2.0, 3.0]).unwrap();
+        let (sr, bound) = r.remove_knots(1E-10).unwrap();
+        assert_eq!(sr.t, s.t);
+        assert_abs_diff_eq!(sr.c[..], s.c[..], epsilon = 1E-10);
+        assert!(bound < 1E-10);
+
+        // with a larger tolerance, the original knots are removed too, with the error within the returned bound
+        let (sr, bound) = s.remove_knots(2.0).unwrap();
+        assert!(sr.t.len() < s.t.len());
+        assert!(bound <= 2.0);
+        let vr = sr.evaluate(&u).unwrap();
+        let deviation = v.chunks(2).zip(vr.chunks(2)).map(|(a, b)| ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()).fold(0.0, f64::max);
+        assert!(deviation <= bound + 1E-12);
+
+        // nothing to remove for a tolerance of zero
+        let (sr, bound) = s.remove_knots(0.0).unwrap();
+        assert_eq!(sr.t, s.t);
+        assert_eq!(bound, 0.0);
+
+        // degree one: knots between collinear control points are removed exactly, others are kept
+        let p: SplineCurve<1, 2> = SplineCurve::new(vec![0.0, 0.0, 1.0, 2.0, 3.0, 3.0], vec![0.0, 1.0, 2.0, 2.0, 0.0, 1.0, 2.0, 0.0]);
+        let (pr, bound) = p.remove_knots(1E-12).unwrap();
+        assert_eq!(pr.t, vec![0.0, 0.0, 2.0, 3.0, 3.0]);
+        assert_eq!(pr.c, vec![0.0, 2.0, 2.0, 0.0, 2.0, 0.0]);
+        assert!(bound < 1E-12);
+
+        // inserted copies of an existing knot are removed one by one, back to its original multiplicity
+        let r = s.insert_knot(2.5, 2).unwrap();
+        let (sr, _) = r.remove_knots(1E-10).unwrap();
+        assert_eq!(sr.t, s.t);
+        assert_abs_diff_eq!(sr.evaluate(&u).unwrap()[..], v[..], epsilon = 1E-10);
+
+        // periodic curves are no longer periodic, and are clamped instead
+        let s: SplineCurve<3, 2> = SplineCurve::periodic(
+            vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            vec![1.0, -1.0, -1.0, 1.0, 1.0, 1.0, -1.0, -1.0],
+        ).unwrap();
+        let (sr, _) = s.remove_knots(10.0).unwrap();
+        assert!(sr.t.len() < s.t.len());
+        assert_eq!(sr.extrapolate, Extrapolate::Clamp);
+        assert_eq!(sr.evaluate(&[4.5]).unwrap(), sr.evaluate(&[4.0]).unwrap());
+    }
+
+    #[test]
+    fn degree_elevation_and_reduction() {
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.5, 2.5, 4.0, 4.0, 4.0, 4.0],
+            vec![0.0, 1.0, 3.0, 4.0, 3.5, 5.0, 6.0, 0.0, 2.0, -1.0, 1.0, 3.0, 0.5, 1.0],
+        );
+        let u: Vec<f64> = (0..=40).map(|i| i as f64 * 0.1).collect();
+        let v = s.evaluate(&u).unwrap();
+
+        let s5: SplineCurve<5, 2> = s.elevate_degree().unwrap();
+        assert_eq!(s5.t, vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.5, 2.5, 2.5, 2.5, 4.0, 4.0, 4.0, 4.0, 4.0, 4.0]);
+        assert_abs_diff_eq!(s5.evaluate(&u).unwrap()[..], v[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s5.evaluate_derivative(&u, 1).unwrap()[..], s.evaluate_derivative(&u, 1).unwrap()[..], epsilon = 1E-10);
+        assert!(matches!(s.elevate_degree::<2>(), Err(SplineError::DegreeMismatch { expected: 3, got: 2 })));
+
+        // a linear curve, with a discontinuity at u=1, elevated to a cubic curve
+        let l: SplineCurve<1, 2> = SplineCurve::new(vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0], vec![0.0, 1.0, 2.0, 3.0, 0.0, 1.0, 1.0, 0.0]);
+        let l3: SplineCurve<3, 2> = l.elevate_degree().unwrap();
+        assert_abs_diff_eq!(l3.evaluate(&u[..21]).unwrap()[..], l.evaluate(&u[..21]).unwrap()[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(l3.evaluate_limit(&[1.0], 0, Side::Left).unwrap()[..], [1.0, 1.0][..], epsilon = 1E-12);
+
+        // elevation only changes a few control points at a time, also for curves with many knots
+        let (c, uc) = cubic_2d();
+        let r = c.refine(&(1..400).map(|i| i as f64 * 0.01).collect::<Vec<f64>>()).unwrap();
+        let r4: SplineCurve<4, 2> = r.elevate_degree().unwrap();
+        let mut knots = r.t.clone();
+        knots.dedup();
+        assert_eq!(r4.t.len(), r.t.len() + knots.len());
+        assert_abs_diff_eq!(r4.evaluate(&uc).unwrap()[..], r.evaluate(&uc).unwrap()[..], epsilon = 1E-10);
+
+        // a piecewise constant curve, elevated to a quadratic curve with triple knots
+        let p: SplineCurve<0, 1> = SplineCurve::new(vec![0.0, 1.0, 2.0, 3.0], vec![1.0, 2.0, 3.0]);
+        let p2: SplineCurve<2, 1> = p.elevate_degree().unwrap();
+        assert_eq!(p2.t, vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0, 3.0, 3.0]);
+        assert_abs_diff_eq!(p2.evaluate(&[0.5, 1.0, 1.5, 2.5]).unwrap()[..], [1.0, 2.0, 2.0, 3.0][..], epsilon = 1E-12);
+
+        // reduction of an elevated curve is exact
+        let (s3, error): (SplineCurve<3, 2>, f64) = s5.reduce_degree(1E-10).unwrap();
+        assert!(error < 1E-10);
+        assert_eq!(s3.t, s.t);
+        assert_abs_diff_eq!(s3.c[..], s.c[..], epsilon = 1E-10);
+
+        // approximation by a quadratic curve, with the reported error the largest deviation
+        let (s2, error): (SplineCurve<2, 2>, f64) = s.refine(&[0.5, 1.5, 2.0, 3.0, 3.5]).unwrap().reduce_degree(0.1).unwrap();
+        let v2 = s2.evaluate(&u).unwrap();
+        let deviation = v.chunks(2).zip(v2.chunks(2)).map(|(a, b)| ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()).fold(0.0, f64::max);
+        assert!(error > 0.0 && deviation <= error + 1E-12);
+        assert!(matches!(s.reduce_degree::<1>(1E-3), Err(SplineError::ToleranceExceeded { .. })));
+
+        // periodic curves are approximated on their domain, and clamped
+        let p: SplineCurve<3, 2> = SplineCurve::periodic(
+            vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            vec![1.0, -1.0, -1.0, 1.0, 1.0, 1.0, -1.0, -1.0],
+        ).unwrap();
+        let (p2, _): (SplineCurve<2, 2>, f64) = p.reduce_degree(1.0).unwrap();
+        assert_eq!(p2.domain(), (0.0, 4.0));
+        assert_eq!(p2.extrapolate, Extrapolate::Clamp);
+    }
+
+    #[test]
+    fn split_and_trim() {
+        let (s, u) = cubic_2d();
+
+        let (s1, s2) = s.split_at(1.7).unwrap();
+        assert_eq!(s1.t, vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.7, 1.7, 1.7, 1.7]);
+        assert_eq!(s2.t, vec![1.7, 1.7, 1.7, 1.7, 2.5, 4.0, 4.0, 4.0, 4.0]);
+        assert_abs_diff_eq!(s1.evaluate(&u[..18]).unwrap()[..], s.evaluate(&u[..18]).unwrap()[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s2.evaluate(&u[17..]).unwrap()[..], s.evaluate(&u[17..]).unwrap()[..], epsilon = 1E-12);
+        // the curves join at the control points at the split
+        assert_abs_diff_eq!([s1.c[4], s1.c[9]][..], [s2.c[0], s2.c[5]][..], epsilon = 1E-12);
+
+        // trimming at knots, and to the full domain
+        let st = s.trim(1.0, 2.5).unwrap();
+        assert_eq!(st.domain(), (1.0, 2.5));
+        assert_eq!(st.c.len(), 8);
+        assert_abs_diff_eq!(st.evaluate(&u[10..=25]).unwrap()[..], s.evaluate(&u[10..=25]).unwrap()[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.trim(0.0, 4.0).unwrap().c[..], s.c[..], epsilon = 1E-12);
+
+        // splitting at an existing knot
+        let (s1, s2) = s.split_at(2.5).unwrap();
+        assert_eq!(s1.t, vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.5, 2.5, 2.5, 2.5]);
+        assert_eq!(s2.t, vec![2.5, 2.5, 2.5, 2.5, 4.0, 4.0, 4.0, 4.0]);
+        assert_abs_diff_eq!(s2.evaluate(&u[25..]).unwrap()[..], s.evaluate(&u[25..]).unwrap()[..], epsilon = 1E-12);
+
+        assert!(matches!(s.split_at(0.0), Err(SplineError::EmptyDomain)));
+        assert!(matches!(s.trim(-1.0, 2.0), Err(SplineError::OutOfDomain { .. })));
+
+        // unclamped curve, with domain 2..=5, trimmed to a clamped curve
+        let s: SplineCurve<2, 1> = SplineCurve::new((0..8).map(|v| v as f64).collect(), vec![1.0, 3.0, 2.0, 4.0, 0.0]);
+        let u: Vec<f64> = (20..=50).map(|i| i as f64 * 0.1).collect();
+        let st = s.trim(2.5, 4.5).unwrap();
+        assert_eq!(st.t, vec![2.5, 2.5, 2.5, 3.0, 4.0, 4.5, 4.5, 4.5]);
+        assert!(st.is_clamped());
+        assert_abs_diff_eq!(st.evaluate(&u[5..=25]).unwrap()[..], s.evaluate(&u[5..=25]).unwrap()[..], epsilon = 1E-12);
+        let (s1, s2) = s.split_at(3.0).unwrap();
+        assert_eq!((s1.domain(), s2.domain()), ((2.0, 3.0), (3.0, 5.0)));
+        assert_abs_diff_eq!(s1.evaluate(&u[..=10]).unwrap()[..], s.evaluate(&u[..=10]).unwrap()[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s2.evaluate(&u[10..]).unwrap()[..], s.evaluate(&u[10..]).unwrap()[..], epsilon = 1E-12);
+    }
+
+    #[test]
+    fn reversal_and_reparametrization() {
+        let s: SplineCurve<3, 2> = SplineCurve::new(
+            vec![-1.0, 0.0, 0.0, 0.0, 1.0, 2.5, 4.0, 4.0, 4.0, 5.0],
+            vec![0.0, 1.0, 3.0, 4.0, 3.5, 5.0, 0.0, 2.0, -1.0, 1.0, 3.0, 0.5],
+        );
+        let u: Vec<f64> = (0..=40).map(|i| i as f64 * 0.1).collect();
+        let ur: Vec<f64> = u.iter().map(|x| 4.0 - x).collect();
+
+        let r = s.reversed();
+        assert_eq!(r.domain(), (0.0, 4.0));
+        assert_eq!(r.t, vec![-1.0, 0.0, 0.0, 0.0, 1.5, 3.0, 4.0, 4.0, 4.0, 5.0]);
+        assert_abs_diff_eq!(r.evaluate(&ur).unwrap()[..], s.evaluate(&u).unwrap()[..], epsilon = 1E-12);
+        let d: Vec<f64> = s.evaluate_derivative(&u, 1).unwrap().iter().map(|x| -x).collect();
+        assert_abs_diff_eq!(r.evaluate_derivative(&ur, 1).unwrap()[..], d[..], epsilon = 1E-12);
+        assert_eq!(r.reversed().t, s.t);
+
+        let p = s.reparametrize(0.0, 1.0).unwrap();
+        assert_eq!(p.domain(), (0.0, 1.0));
+        let up: Vec<f64> = u.iter().map(|x| x / 4.0).collect();
+        assert_abs_diff_eq!(p.evaluate(&up).unwrap()[..], s.evaluate(&u).unwrap()[..], epsilon = 1E-12);
+        let d: Vec<f64> = s.evaluate_derivative(&u, 2).unwrap().iter().map(|x| 16.0 * x).collect();
+        assert_abs_diff_eq!(p.evaluate_derivative(&up, 2).unwrap()[..], d[..], epsilon = 1E-10);
+        assert!(matches!(s.reparametrize(1.0, 1.0), Err(SplineError::EmptyDomain)));
+
+        // unclamped curve, with a domain which can not be mirrored exactly using the sum of its ends
+        let s: SplineCurve<3, 1> = SplineCurve::new(vec![0.0, 0.1, 0.1, 0.1, 0.3, 0.5, 0.7, 0.7, 0.7, 0.8], vec![0.0, 1.0, -1.0, 2.0, 0.5, 1.0]);
+        let r = s.reversed();
+        assert_eq!(r.domain(), (0.1, 0.7));
+        assert_eq!(r.reversed().t, s.t);
+        assert_abs_diff_eq!(r.evaluate(&[0.1, 0.25, 0.7]).unwrap()[..], s.evaluate(&[0.7, 0.55, 0.1]).unwrap()[..], epsilon = 1E-12);
+    }
+
+    #[test]
+    fn transforms() {
+        let (s, u) = cubic_2d();
+        let v = s.evaluate(&u).unwrap();
+
+        // affine invariance: transforming the control points transforms the curve
+        let m = [[2.0, -1.0], [0.5, 3.0]];
+        let st = s.transform(m, [1.0, -2.0]);
+        let vt: Vec<f64> = v.chunks(2).flat_map(|p| [m[0][0] * p[0] + m[0][1] * p[1] + 1.0, m[1][0] * p[0] + m[1][1] * p[1] - 2.0]).collect();
+        assert_abs_diff_eq!(st.evaluate(&u).unwrap()[..], vt[..], epsilon = 1E-12);
+
+        let vr: Vec<f64> = v.chunks(2).flat_map(|p| [-p[1], p[0]]).collect();
+        assert_abs_diff_eq!(s.rotate(std::f64::consts::FRAC_PI_2).evaluate(&u).unwrap()[..], vr[..], epsilon = 1E-12);
+        let vs: Vec<f64> = v.chunks(2).flat_map(|p| [2.0 * p[0] + 1.0, -p[1] + 1.0]).collect();
+        assert_abs_diff_eq!(s.scale_axes([2.0, -1.0]).translate([1.0, 1.0]).evaluate(&u).unwrap()[..], vs[..], epsilon = 1E-12);
+        assert_abs_diff_eq!(s.scale(0.5).c[..], s.c.iter().map(|c| 0.5 * c).collect::<Vec<f64>>()[..], epsilon = 1E-12);
+
+        // unclamped curves, which do not pass through their end control points, are transformed too
+        let s: SplineCurve<2, 2> = SplineCurve::new((0..9).map(|v| v as f64).collect(), vec![1.0, 3.0, 2.0, 4.0, 0.0, 1.0, 0.0, 1.0, 3.0, 2.0, 1.0, 0.0]);
+        let u: Vec<f64> = (20..=60).map(|i| i as f64 * 0.1).collect();
+        let vr: Vec<f64> = s.evaluate(&u).unwrap().chunks(2).flat_map(|p| [p[1] - 1.0, -p[0]]).collect();
+        assert_abs_diff_eq!(s.rotate(-std::f64::consts::FRAC_PI_2).translate([-1.0, 0.0]).evaluate(&u).unwrap()[..], vr[..], epsilon = 1E-12);
+
+        // rotation around the z-axis in 3D, and a third of a turn around the diagonal, permuting the axes
+        let s3: SplineCurve<1, 3> = SplineCurve::new(vec![0.0, 0.0, 1.0, 2.0, 2.0], vec![1.0, 0.0, 2.0, 0.0, 1.0, 3.0, 0.0, 0.0, 1.0]);
+        let r = s3.rotate([0.0, 0.0, 2.0], std::f64::consts::FRAC_PI_2).unwrap();
+        assert_abs_diff_eq!(r.c[..], [0.0, -1.0, -3.0, 1.0, 0.0, 2.0, 0.0, 0.0, 1.0][..], epsilon = 1E-12);
+        let r = s3.rotate([1.0, 1.0, 1.0], 2.0 * std::f64::consts::FRAC_PI_3).unwrap();
+        assert_abs_diff_eq!(r.c[..], [0.0, 0.0, 1.0, 1.0, 0.0, 2.0, 0.0, 1.0, 3.0][..], epsilon = 1E-12);
+        assert!(matches!(s3.rotate([0.0; 3], 1.0), Err(SplineError::InvalidAxis)));
+        assert!(matches!(s3.rotate([f64::NAN, 0.0, 1.0], 1.0), Err(SplineError::InvalidAxis)));
+    }
+
+    #[test]
+    fn control_points() {
+        let mut s: SplineCurve<3, 2> = SplineCurve::from_control_points(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 2.5, 4.0, 4.0, 4.0, 4.0],
+            vec![[0.0, 0.0], [1.0, 2.0], [3.0, -1.0], [4.0, 1.0], [3.5, 3.0], [5.0, 0.5]],
+        );
+        assert_eq!(s.c, cubic_2d().0.c);
+        assert_eq!(s.control_point_count(), 6);
+        assert_eq!(s.control_point(2), Some([3.0, -1.0]));
+        assert_eq!(s.control_point(6), None);
+        let points: Vec<[f64; 2]> = s.control_points().collect();
+        assert_eq!(points[5], [5.0, 0.5]);
+
+        // curves start and end at their first and last control points, for clamped knot vectors
+        s.set_control_point(5, [6.0, -1.0]);
+        assert_eq!(s.c[5], 6.0);
+        assert_eq!(s.c[11], -1.0);
+        assert_abs_diff_eq!(s.evaluate(&[4.0]).unwrap()[..], [6.0, -1.0][..], epsilon = 1E-12);
+
+        // degree one curves pass through all their control points, and change only next to a changed point
+        let mut p: SplineCurve<1, 3> = SplineCurve::from_control_points(
+            vec![0.0, 0.0, 1.0, 2.0, 3.0, 3.0],
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 1.0], [1.0, 1.0, 2.0], [0.0, 1.0, 3.0]],
+        );
+        let points: Vec<f64> = p.control_points().flatten().collect();
+        assert_abs_diff_eq!(p.evaluate(&[0.0, 1.0, 2.0, 3.0]).unwrap()[..], points[..], epsilon = 1E-12);
+        p.set_control_point(3, [2.0, 2.0, 2.0]);
+        assert_abs_diff_eq!(p.evaluate(&[1.0, 2.5]).unwrap()[..], [1.0, 0.0, 1.0, 1.5, 1.5, 2.0][..], epsilon = 1E-12);
+
+        // periodic curves keep their repeated control points equal, at both ends of the control polygon
+        let mut s: SplineCurve<2, 2> = SplineCurve::periodic(
+            vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            vec![1.0, -1.0, -1.0, 1.0, 1.0, 1.0, -1.0, -1.0],
+        ).unwrap();
+        assert_eq!(s.control_point_count(), 6);
+        s.set_control_point(1, [-2.0, 2.0]);
+        s.set_control_point(4, [2.0, 0.0]);
+        assert_eq!(s.control_point(5), Some([-2.0, 2.0]));
+        assert_eq!(s.control_point(0), Some([2.0, 0.0]));
+        for order in 0..2 {
+            let v = s.evaluate_derivative(&[0.0, 4.0], order).unwrap();
+            assert_abs_diff_eq!(v[0..2], v[2..4], epsilon = 1E-12);
+        }
+
+        // but not after a knot insertion, which breaks the periodic structure, or for a clamped knot vector
+        let mut si = s.insert_knot(0.5, 1).unwrap();
+        let c = si.c.clone();
+        si.set_control_point(5, [7.0, 8.0]);
+        assert_eq!(si.control_point_count(), 7);
+        for j in (0..7).filter(|&j| j != 5) {
+            assert_eq!(si.control_point(j), Some([c[j], c[j + 7]]));
+        }
+        let mut sc: SplineCurve<3, 1> = SplineCurve::new(
+            vec![0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0],
+            vec![0.0, 1.0, 3.0, 2.0],
+        ).with_extrapolation(Extrapolate::Periodic);
+        sc.set_control_point(0, [5.0]);
+        assert_eq!(sc.c, vec![5.0, 1.0, 3.0, 2.0]);
+    }
 }
@@ -2,12 +2,16 @@ use std::iter::repeat;
 
 use crate::SplineCurve;
 
-use super::Result;
+use super::{Result, SplineError};
 
 /**
  * General B-Spline Curve Knot/Coefficient Representation
+ * 
+ * A static collection of spline curves, with their knots and coefficients stored in shared arrays, and keys
+ * giving the ranges of the knots and coefficients of each curve. Knot vectors can be stored in full, which is
+ * required for unclamped curves, or, for clamped curves, with the end knots only stored once, in which case
+ * they are repeated `K` times when the curve is created.
  */
-
 pub struct SplineCurves<const K: usize, const N: usize, const NK: usize, const NT:usize, const NC: usize> {
     keys: [(&'static str, [usize;2], [usize;2]); NK],
     t: [i32; NT], // Knot values
@@ -23,7 +27,10 @@ impl<const K: usize, const N: usize, const NK: usize, const NC: usize, const NT:
         for (s, [ts,te], [cs, ce]) in self.keys {
             if key == s {
                 let ti = &self.t[ts..te];
-                let t: Vec<f64> = 
+                let t: Vec<f64> = if ti.len() == (ce - cs) / N + K + 1 {
+                    // full knot vector, clamped or not
+                    ti.iter().map(|&v| v as f64).collect()
+                } else {
                     repeat(ti[0] as f64)
                     .take(K)
                     .chain(
@@ -33,26 +40,33 @@ impl<const K: usize, const N: usize, const NK: usize, const NC: usize, const NT:
                     ).chain(
                         repeat(ti[ti.len()-1] as f64)
                         .take(K)
-                    ).collect();
+                    ).collect()
+                };
 
                 return Ok(SplineCurve::new(t, self.c[cs..ce].to_owned()))
             }
         }
         
-        Err("Key not found".into())
+        Err(SplineError::KeyNotFound(key.to_string()))
     }
 
     pub fn evaluate(&self, key: &str, u: &[f64]) -> Result<Vec<f64>> {
         let sc = self.spline_curve(key)?;
         sc.evaluate(u)
     }
+
+    pub fn integrate(&self, key: &str, a: f64, b: f64) -> Result<[f64; N]> {
+        let sc = self.spline_curve(key)?;
+        sc.integrate(a, b)
+    }
 }
 
 
 
 #[cfg(test)]
 mod tests {
-    use crate::{SplineCurves, Result};
+    use crate::{SplineCurves, SplineError, Result};
+    use approx::assert_abs_diff_eq;
     static MUNSELL_MATT: SplineCurves<3,1,11,131,153> = SplineCurves::new(
         [("2.5R9/2", [0, 14], [0, 16]), ("2.5R8/2", [14, 28], [16, 32]), ("2.5R7/2", [28, 41], [32, 47]), ("2.5R6/2",
             [41, 54], [47, 62]), ("2.5R5/2", [54, 63], [62, 73]), ("2.5R4/2", [63, 72], [73, 84]), ("2.5R3/2", [72, 78],
@@ -90,4 +104,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn static_munsell_matt_band_integrals() -> Result<()>{
+        let sc = MUNSELL_MATT.spline_curve("2.5R8/4")?;
+        let total = MUNSELL_MATT.integrate("2.5R8/4", 380.0, 800.0)?[0];
+        let bands: f64 = (0..42).map(|i| sc.integrate(380.0 + 10.0 * i as f64, 390.0 + 10.0 * i as f64).unwrap()[0]).sum();
+        assert_abs_diff_eq!(total, bands, epsilon = 1E-10);
+
+        // trapezoidal rule on a fine grid, in a band only partially covering knot intervals
+        let u: Vec<f64> = (0..=4000).map(|i| 452.0 + i as f64 * 0.01).collect();
+        let v = sc.evaluate(&u)?;
+        let trapezoidal: f64 = v.windows(2).map(|w| (w[0] + w[1]) * 0.005).sum();
+        assert_abs_diff_eq!(sc.integrate(452.0, 492.0)?[0], trapezoidal, epsilon = 1E-6);
+
+        assert!(matches!(MUNSELL_MATT.spline_curve("5R9/2"), Err(SplineError::KeyNotFound(key)) if key == "5R9/2"));
+        Ok(())
+    }
+
 }